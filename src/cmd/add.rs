@@ -1,27 +1,79 @@
+use super::args::Args;
+use super::error::InvalidArguments;
 use super::{Command, Dispatch};
+use crate::net::{self, meta};
+use crate::store::{bookmark::Bookmark, url::Url};
 use anyhow::Result;
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    if args.len() < 2 {
-        anyhow::bail!("Invalid arguments");
+    let args = Args::parse(args, &["offline", "keywords"], &[])?;
+
+    // The name may be left out, in which case the first argument is the URL
+    let (name, url, tags) = match args.positional() {
+        [url] => (None, url, &[][..]),
+        [url, tags @ ..] if Url::parse(url).is_ok() => (None, url, tags),
+        [name, url, tags @ ..] => (Some(name), url, tags),
+        _ => anyhow::bail!(InvalidArguments),
+    };
+
+    let url = Url::with_policy(url, dispatch.store().url_policy)?;
+    let tags = tags.to_vec();
+
+    if !url.is_valid() {
+        eprintln!("Warning: '{}' is not a valid URL", url);
+    }
+    if let Some(existing) = dispatch.store().find_url(url.as_str()) {
+        eprintln!("Warning: URL is already bookmarked as {}", existing);
     }
 
-    let name = &args[0];
-    let url = &args[1];
-    let tags = args.iter().skip(2).map(|s| s.to_string()).collect();
+    let mut bookmark = Bookmark::new(name.cloned().unwrap_or_default(), url, tags);
+    bookmark.collection = dispatch.collection().map(str::to_string);
+    if name.is_none() {
+        describe(&mut bookmark, args.flag("offline"), args.flag("keywords"));
+    }
 
-    let id = dispatch
-        .store_mut()
-        .create(name.to_string(), url.to_string(), tags);
+    let id = dispatch.store_mut().add(bookmark);
 
     println!("Created bookmark with ID {}", id);
 
     Ok(())
 }
 
+/// Fills in a bookmark's name and description from its page, falling back to its URL.
+fn describe(bookmark: &mut Bookmark, offline: bool, keywords: bool) {
+    let page = match bookmark.url.parsed() {
+        Some(url) if !offline && url.scheme().starts_with("http") => {
+            let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+            meta::fetch(&agent, url.as_str())
+                .inspect_err(|e| eprintln!("Warning: {:#}; naming bookmark after its URL", e))
+                .ok()
+        }
+        _ => None,
+    };
+    let page = page.unwrap_or_default();
+
+    bookmark.name = page
+        .title
+        .unwrap_or_else(|| meta::name_from_url(&bookmark.url));
+    bookmark.description = page.description;
+
+    let suggested: Vec<String> = page
+        .keywords
+        .into_iter()
+        .filter(|keyword| !bookmark.tags.contains(keyword))
+        .collect();
+    if keywords {
+        bookmark.tags.extend(suggested);
+    } else if !suggested.is_empty() {
+        println!("Suggested tags: {}", suggested.join(", "));
+    }
+}
+
 inventory::submit!(Command {
     name: "add",
     description: "Add a new bookmark",
-    usage: "add <name> <url> [tags...]",
+    usage: "add [name] <url> [tags...] [--offline] [--keywords]",
+    destructive: false,
+    accepts_input: false,
     run,
 });
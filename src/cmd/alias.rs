@@ -0,0 +1,97 @@
+use super::error::{InvalidArguments, MissingArgument};
+use super::{Command, Dispatch};
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    match args {
+        [] => {
+            let aliases = &dispatch.config().aliases;
+            if aliases.is_empty() {
+                println!("No aliases");
+            }
+            for (name, body) in aliases {
+                println!("{} = {}", name, body);
+            }
+            return Ok(());
+        }
+        [name] => {
+            match dispatch.config().aliases.get(name) {
+                Some(body) => println!("{} = {}", name, body),
+                None => println!("{} is not an alias", name),
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let mut config = dispatch.config().clone();
+    match args {
+        [flag, name] if flag == "--remove" => {
+            if config.aliases.remove(name).is_none() {
+                anyhow::bail!("{} is not an alias", name);
+            }
+            println!("Removed alias {}", name);
+        }
+        [name, rest @ ..] if !name.starts_with("--") => {
+            let body = match rest {
+                [eq, body @ ..] if eq == "=" => body,
+                body => body,
+            };
+            // A single argument is the body as typed; several are quoted again so they split
+            // the same way when the alias runs
+            let body = match body {
+                [] => anyhow::bail!(MissingArgument("alias body".to_string())),
+                [body] => body.clone(),
+                body => shlex::try_join(body.iter().map(String::as_str))?,
+            };
+            println!("{} = {}", name, body);
+            config.aliases.insert(name.clone(), body);
+        }
+        _ => anyhow::bail!(InvalidArguments),
+    }
+    dispatch.update_config(config)?;
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "alias",
+    description: "List, define or remove command aliases",
+    usage: "alias [name [= body...]] | alias --remove <name> (quote bodies with ';' to run several commands)",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expansion() {
+        let mut dispatch = Dispatch::default();
+        let mut config = dispatch.config().clone();
+        for (name, body) in [
+            ("bm", "add --offline"),
+            ("two", "bm one https://one.example; bm"),
+            ("add", "add --offline"),
+            ("ping", "pong"),
+            ("pong", "ping"),
+        ] {
+            config.aliases.insert(name.to_string(), body.to_string());
+        }
+        dispatch.set_config(config).unwrap();
+
+        dispatch
+            .run_line("two 'two words' https://two.example")
+            .unwrap();
+        dispatch
+            .run_line("add three https://three.example")
+            .unwrap();
+        let names: Vec<_> = dispatch.store().iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["one", "two words", "three"]);
+
+        let error = dispatch.run_line("ping").unwrap_err();
+        assert_eq!(error.to_string(), "Alias cycle: ping -> pong -> ping");
+    }
+}
@@ -0,0 +1,46 @@
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use crate::net::{self, archive};
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &["inline"], &[])?;
+
+    let [id] = args.positional() else {
+        anyhow::bail!(InvalidArguments);
+    };
+    let id = dispatch.selected_one(id)?;
+
+    let store = dispatch.store();
+    let dir = store
+        .archive_dir()
+        .context("The store has no file yet; save it before archiving pages")?;
+    let url = store
+        .get(id)
+        .context("Bookmark not found")?
+        .url
+        .as_str()
+        .to_string();
+
+    let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+    let page = archive::download(&agent, &url, args.flag("inline"))?;
+    let (hash, path) = archive::write(&dir, &page.content, &page.mime)?;
+
+    if let Some(mut bookmark) = dispatch.store_mut().get_mut(id) {
+        bookmark.untracked().archive = Some(hash);
+    }
+
+    println!("Archived bookmark {} to {}", id, path.display());
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "archive",
+    description: "Save a copy of a bookmarked page",
+    usage: "archive <selection> [--inline]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
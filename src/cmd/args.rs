@@ -0,0 +1,64 @@
+use super::error::{InvalidArgument, MissingArgument};
+use anyhow::Result;
+
+/// Command arguments split into positional arguments and `--flag` options.
+pub struct Args {
+    positional: Vec<String>,
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    /// Parses raw command arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The raw arguments passed to the command.
+    /// * `switches` - Flags that take no value (e.g. `untagged` for `--untagged`).
+    /// * `options` - Flags that take a value (e.g. `sort` for `--sort name`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an unknown flag is given or an option is missing its value.
+    pub fn parse(args: &[String], switches: &[&str], options: &[&str]) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+
+            if switches.contains(&flag) {
+                flags.push((flag.to_string(), None));
+            } else if options.contains(&flag) {
+                let value = iter.next().ok_or_else(|| MissingArgument(arg.clone()))?;
+                flags.push((flag.to_string(), Some(value.clone())));
+            } else {
+                anyhow::bail!(InvalidArgument(arg.clone()));
+            }
+        }
+
+        Ok(Self { positional, flags })
+    }
+
+    /// Returns the positional arguments.
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Returns `true` if the given flag was passed.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|(flag, _)| flag == name)
+    }
+
+    /// Returns the value of the last occurrence of the given option.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .rev()
+            .find(|(flag, _)| flag == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
@@ -0,0 +1,30 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use crate::store::collection;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let collection = match args {
+        [] => None,
+        [path] => collection::resolve(dispatch.collection(), path)?,
+        _ => anyhow::bail!(InvalidArguments),
+    };
+
+    if let Some(collection) = &collection
+        && !dispatch.store().collections().contains(collection)
+    {
+        anyhow::bail!("Collection '{}' not found", collection);
+    }
+    dispatch.set_collection(collection);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "cd",
+    description: "Change the current collection",
+    usage: "cd [collection|..|/]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
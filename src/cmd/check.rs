@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use super::args::Args;
+use super::{Command, Dispatch};
+use crate::net::{self, check};
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+/// The web bookmarks picked by the arguments of `check` and `resolve`, and how many of them to
+/// request at once.
+pub struct Targets {
+    /// Bookmark IDs and their URLs, for bookmarks with `http` or `https` URLs.
+    pub urls: Vec<(usize, String)>,
+    pub jobs: usize,
+}
+
+impl Targets {
+    /// Reads `--jobs` and the selection arguments, which default to every bookmark.
+    pub fn from_args(dispatch: &Dispatch, args: &Args) -> Result<Self> {
+        let jobs = match args.value("jobs") {
+            Some(jobs) => jobs.parse().context("Invalid number of jobs")?,
+            None => check::DEFAULT_JOBS,
+        };
+
+        let ids: Vec<usize> = match args.positional() {
+            [] => dispatch
+                .store()
+                .iter()
+                .map(|bookmark| bookmark.id)
+                .collect(),
+            selections => dispatch.selection(selections)?,
+        };
+        let store = dispatch.store();
+
+        let mut urls = Vec::new();
+        for id in ids {
+            let bookmark = store.get(id).context("Bookmark not found")?;
+            if bookmark
+                .url
+                .parsed()
+                .is_some_and(|url| url.scheme().starts_with("http"))
+            {
+                urls.push((id, bookmark.url.as_str().to_string()));
+            }
+        }
+
+        Ok(Self { urls, jobs })
+    }
+}
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &[], &["jobs", "timeout", "tag"])?;
+
+    let timeout = match args.value("timeout") {
+        Some(seconds) => seconds
+            .parse()
+            .ok()
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+            .with_context(|| format!("Invalid timeout: '{}'", seconds))?,
+        None => net::DEFAULT_TIMEOUT,
+    };
+
+    let targets = Targets::from_args(dispatch, &args)?;
+
+    println!("Checking {} bookmarks...", targets.urls.len());
+    let agent = net::agent(timeout, net::DEFAULT_MAX_REDIRECTS);
+    let mut results = check::check_all(&agent, targets.urls, targets.jobs);
+    results.sort_by_key(|(id, _)| *id);
+
+    let now = Utc::now();
+    let mut dead = 0;
+    for (id, result) in results {
+        let store = dispatch.store_mut();
+        let Some(mut bookmark) = store.get_mut(id) else {
+            continue;
+        };
+
+        // Checking a link does not count as modifying the bookmark
+        let final_url = result
+            .final_url
+            .filter(|url| url != bookmark.url.as_str())
+            .map(Into::into);
+        let checked = bookmark.untracked();
+        checked.checked = Some(now);
+        checked.status = Some(result.status);
+        checked.final_url = final_url;
+
+        let is_dead = result.status.is_dead();
+        if is_dead {
+            dead += 1;
+            let reason = result.error.unwrap_or_else(|| result.status.to_string());
+            println!("{} ({})", bookmark, reason);
+        }
+        let mut tags = bookmark.tags.clone();
+        drop(bookmark);
+
+        // Only touch bookmarks whose tags change, so the rest keep their modification time
+        if let Some(tag) = args.value("tag")
+            && tags.iter().any(|t| t == tag) != is_dead
+        {
+            tags.retain(|t| t != tag);
+            if is_dead {
+                tags.push(tag.to_string());
+            }
+            store.set_tags(id, tags);
+        }
+    }
+
+    println!("{} dead links found", dead);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "check",
+    description: "Check bookmarks for dead links",
+    usage: "check [selection...] [--jobs <n>] [--timeout <seconds>] [--tag <tag>]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
@@ -0,0 +1,44 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use crate::config::Config;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    match args {
+        [] => {
+            for (key, value) in dispatch.config().entries() {
+                println!("{} = {}", key, value);
+            }
+        }
+        [cmd] if cmd == "path" => match Config::path() {
+            Some(path) => println!("{}", path.display()),
+            None => println!("No config directory found"),
+        },
+        [cmd, key] if cmd == "get" => match dispatch.config().get(key)? {
+            Some(value) => println!("{}", value),
+            None => println!("{} is not set", key),
+        },
+        [cmd, key, value @ ..] if cmd == "set" && !value.is_empty() => {
+            let config = dispatch.config().with(key, &value.join(" "))?;
+            let path = dispatch.update_config(config)?;
+            println!("Saved {}", path.display());
+        }
+        [cmd, key] if cmd == "unset" => {
+            let config = dispatch.config().without(key)?;
+            let path = dispatch.update_config(config)?;
+            println!("Saved {}", path.display());
+        }
+        _ => anyhow::bail!(InvalidArguments),
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "config",
+    description: "Show or change settings",
+    usage: "config [get <key> | set <key> <value> | unset <key> | path]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
@@ -0,0 +1,87 @@
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::template;
+use super::transfer;
+use super::{Command, Dispatch};
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &[], &["template", "to"])?;
+
+    // Copying to another store rather than the clipboard
+    if let Some(name) = args.value("to") {
+        let ids = match (dispatch.take_input(), args.positional()) {
+            (Some(input), []) => input,
+            (None, selections) if !selections.is_empty() => dispatch.selection(selections)?,
+            _ => anyhow::bail!(InvalidArguments),
+        };
+        let count = transfer::transfer(dispatch, &ids, name)?;
+        println!("Copied {} bookmarks to {}", count, name);
+        return Ok(());
+    }
+
+    let [id] = args.positional() else {
+        anyhow::bail!(InvalidArguments);
+    };
+    let id = dispatch.selected_one(id)?;
+    let template = template::resolve(args.value("template").unwrap_or("url"));
+
+    let bookmark = dispatch.store().get(id).context("Bookmark not found")?;
+    let text = template::expand(template, &bookmark);
+    dispatch.clipboard()?.copy(&text)?;
+    println!("Copied {}", text);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "copy",
+    description: "Copy a bookmark's URL to the clipboard, or bookmarks to another store",
+    usage: "copy <selection> [--template url|markdown|plain|<template>] | copy <selection...> --to <store>",
+    destructive: false,
+    accepts_input: true,
+    run,
+});
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::store::bookmark::Bookmark;
+    use crate::sys::clipboard::Clipboard;
+
+    struct FakeClipboard(Rc<RefCell<Vec<String>>>);
+
+    impl Clipboard for FakeClipboard {
+        fn copy(&mut self, text: &str) -> Result<()> {
+            self.0.borrow_mut().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy() {
+        let copied = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatch = Dispatch::default();
+        dispatch.set_clipboard(Box::new(FakeClipboard(copied.clone())));
+        dispatch.store_mut().add(Bookmark::new(
+            "Rust".to_string(),
+            "https://www.rust-lang.org/".into(),
+            vec![],
+        ));
+
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        run(&mut dispatch, &args(&["0"])).unwrap();
+        run(&mut dispatch, &args(&["0", "--template", "markdown"])).unwrap();
+        assert!(run(&mut dispatch, &args(&["1"])).is_err());
+
+        assert_eq!(
+            *copied.borrow(),
+            vec![
+                "https://www.rust-lang.org/",
+                "[Rust](https://www.rust-lang.org/)"
+            ]
+        );
+    }
+}
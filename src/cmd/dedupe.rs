@@ -0,0 +1,44 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!(InvalidArguments);
+    }
+
+    let store = dispatch.store();
+    let groups = store.duplicates();
+    if groups.is_empty() {
+        println!("No duplicate bookmarks");
+        return Ok(());
+    }
+
+    let mut preview = String::new();
+    for group in &groups {
+        for (i, bookmark) in group.iter().filter_map(|&id| store.get(id)).enumerate() {
+            let action = if i == 0 { "keep " } else { "merge" };
+            preview.push_str(&format!("{} {}\n", action, bookmark));
+        }
+    }
+    let count = groups.iter().map(|group| group.len() - 1).sum::<usize>();
+    if !dispatch.confirm(&preview, &format!("Merge {} duplicate bookmarks?", count))? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    let removed = dispatch.store_mut().dedupe();
+    dispatch.set_last(Vec::new());
+    println!("Merged {} duplicate bookmarks", removed);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "dedupe",
+    description: "Merge bookmarks that share the same URL, keeping the first",
+    usage: "dedupe [--yes]",
+    destructive: true,
+    accepts_input: false,
+    run,
+});
@@ -1,14 +1,47 @@
+use std::{collections::BTreeMap, io::IsTerminal, path::PathBuf};
+
 use anyhow::{Context, Result};
 use rustyline::{DefaultEditor, error::ReadlineError};
 
 use super::Command;
-use crate::store::Store;
+use super::error::MissingArgument;
+use super::line::{self, Connector};
+use super::render::OutputFormat;
+use super::table::Style;
+use crate::config::Config;
+use crate::store::{Store, collection};
+use crate::sys::{
+    clipboard::{self, Clipboard},
+    editor::Editor,
+    opener::Opener,
+};
+
+/// The name of the store that is open when inno starts.
+pub const DEFAULT_STORE: &str = "default";
+
+/// Commands whose arguments are passed on as given, without taking global options from them,
+/// since they hold other commands (e.g. `alias j show --format json`).
+const VERBATIM: &[&str] = &["alias"];
 
 /// A dispatcher for handling commands and managing state.
 pub struct Dispatch {
     store: Store,
+    store_name: String,
+    stores: BTreeMap<String, Store>, // open stores other than the active one
     commands: Vec<&'static Command>,
     editor: DefaultEditor,
+    clipboard: Option<Box<dyn Clipboard>>,
+    format: OutputFormat,
+    style: Style,
+    config: Config,
+    expanding: Vec<String>,     // aliases being expanded, innermost last
+    input: Option<Vec<usize>>,  // bookmarks piped into the running command
+    piped: bool,                // whether the running command's selection is piped onwards
+    output: Option<Vec<usize>>, // bookmarks selected by the running command
+    last: Vec<usize>,           // results of the last search
+    collection: Option<String>, // the current collection, or `None` at the top level
+    interactive: bool,          // whether the user can answer confirmation prompts
+    assume_yes: bool,           // whether to go ahead without confirmation
 }
 
 impl Dispatch {
@@ -21,8 +54,113 @@ impl Dispatch {
     pub fn new(store: Store, commands: Vec<&'static Command>) -> Self {
         Self {
             store,
+            store_name: DEFAULT_STORE.to_string(),
+            stores: BTreeMap::new(),
             commands,
             editor: DefaultEditor::new().expect("Failed to create editor"),
+            clipboard: None,
+            format: OutputFormat::default(),
+            style: Style::detect(),
+            config: Config::default(),
+            expanding: Vec::new(),
+            input: None,
+            piped: false,
+            output: None,
+            last: Vec::new(),
+            collection: None,
+            interactive: std::io::stdin().is_terminal(),
+            assume_yes: false,
+        }
+    }
+
+    /// Creates a `Dispatch` with all commands, set up by a config.
+    ///
+    /// The configured store is not loaded yet; see [`Dispatch::load_store`].
+    pub fn from_config(config: Config) -> Result<Self> {
+        let mut dispatch = Self::default();
+        dispatch.set_config(config)?;
+        Ok(dispatch)
+    }
+
+    /// Loads the configured store if its file exists; otherwise it is created on the first save.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the store empty and without a file, if the store cannot be
+    /// loaded. The config stays in place either way.
+    pub fn load_store(&mut self) -> Result<()> {
+        let Some(path) = self.config.store_path() else {
+            return Ok(());
+        };
+        if !path.exists() {
+            self.store.path = Some(path);
+            return Ok(());
+        }
+
+        // Load into a store of its own, so that a failure part way through leaves nothing behind
+        let mut store = Store::new();
+        store.url_policy = self.store.url_policy;
+        store.normalize_tags = self.store.normalize_tags;
+        store.load(&path)?;
+        self.store = store;
+        Ok(())
+    }
+
+    /// Returns the current config.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Replaces the config, applying its settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the current config in place, if a setting is invalid.
+    pub fn set_config(&mut self, config: Config) -> Result<()> {
+        let format: OutputFormat = config.format.parse()?;
+        let url_policy = config.url_policy.parse()?;
+        let mut style = Style::detect();
+        match config.color.as_str() {
+            "auto" => {}
+            "always" => style.color = true,
+            "never" => style.color = false,
+            other => anyhow::bail!(
+                "Invalid color setting: '{}' (expected auto, always or never)",
+                other
+            ),
+        }
+
+        self.format = format;
+        self.style = style;
+        for store in std::iter::once(&mut self.store).chain(self.stores.values_mut()) {
+            store.url_policy = url_policy;
+            store.normalize_tags = config.normalize_tags;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Applies a changed config and writes it to the config file, returning the file's path.
+    pub fn update_config(&mut self, config: Config) -> Result<PathBuf> {
+        let path = Config::path().context("Cannot find the config directory; set $INNO_CONFIG")?;
+        self.set_config(config)?;
+        self.config.write(&path)?;
+        Ok(path)
+    }
+
+    /// Returns the opener for URLs: the configured one, or else `$INNO_OPENER` or the default.
+    pub fn opener(&self) -> Opener {
+        match &self.config.opener {
+            Some(command) => Opener::new(command.clone()),
+            None => Opener::from_env(),
+        }
+    }
+
+    /// Returns the editor for text: the configured one, or else `$VISUAL` or `$EDITOR`.
+    pub fn text_editor(&self) -> Editor {
+        match &self.config.editor {
+            Some(command) => Editor::new(command.clone()),
+            None => Editor::from_env(),
         }
     }
 
@@ -46,8 +184,118 @@ impl Dispatch {
         &mut self.store
     }
 
+    /// Returns the name of the active store.
+    pub fn store_name(&self) -> &str {
+        &self.store_name
+    }
+
+    /// Returns the names of all open stores with the stores, the active one among them.
+    pub fn stores(&self) -> Vec<(&str, &Store)> {
+        let mut stores: Vec<_> = self
+            .stores
+            .iter()
+            .map(|(name, store)| (name.as_str(), store))
+            .collect();
+        stores.push((&self.store_name, &self.store));
+        stores.sort_by_key(|(name, _)| *name);
+        stores
+    }
+
+    /// Returns a mutable reference to an open store other than the active one.
+    pub fn other_store_mut(&mut self, name: &str) -> Result<&mut Store> {
+        if name == self.store_name {
+            anyhow::bail!("'{}' is the active store", name);
+        }
+        self.stores
+            .get_mut(name)
+            .with_context(|| format!("No store named '{}' is open", name))
+    }
+
+    /// Opens a store under a name, set up like the active one.
+    pub fn open_store(&mut self, name: &str, mut store: Store) -> Result<()> {
+        if name == self.store_name || self.stores.contains_key(name) {
+            anyhow::bail!("A store named '{}' is already open", name);
+        }
+        store.url_policy = self.store.url_policy;
+        store.normalize_tags = self.store.normalize_tags;
+        self.stores.insert(name.to_string(), store);
+        Ok(())
+    }
+
+    /// Closes a store other than the active one, returning it.
+    pub fn close_store(&mut self, name: &str) -> Result<Store> {
+        self.other_store_mut(name)?;
+        Ok(self.stores.remove(name).expect("store was just found"))
+    }
+
+    /// Makes an open store the active one.
+    ///
+    /// IDs and collections belong to a store, so the last search results and current collection
+    /// are forgotten.
+    pub fn use_store(&mut self, name: &str) -> Result<()> {
+        if name == self.store_name {
+            return Ok(());
+        }
+        let store = self
+            .stores
+            .remove(name)
+            .with_context(|| format!("No store named '{}' is open", name))?;
+        let previous = std::mem::replace(&mut self.store, store);
+        let previous_name = std::mem::replace(&mut self.store_name, name.to_string());
+        self.stores.insert(previous_name, previous);
+        self.last.clear();
+        self.collection = None;
+        Ok(())
+    }
+
+    /// Returns the clipboard, detecting which one to use the first time it is needed.
+    pub fn clipboard(&mut self) -> Result<&mut dyn Clipboard> {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(clipboard::detect()?);
+        }
+        Ok(self
+            .clipboard
+            .as_deref_mut()
+            .expect("clipboard was just set"))
+    }
+
+    /// Replaces the clipboard used by commands.
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn Clipboard>) {
+        self.clipboard = Some(clipboard);
+    }
+
+    /// Returns the format listing commands should write their output in.
+    pub fn format(&self) -> &OutputFormat {
+        &self.format
+    }
+
+    /// Returns how output is decorated for the terminal.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Sets the default output format.
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
+    /// Sets whether the user can answer confirmation prompts; by default, if stdin is a terminal.
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
+    /// Sets whether destructive commands go ahead without asking, as if given `--yes`.
+    pub fn set_assume_yes(&mut self, assume_yes: bool) {
+        self.assume_yes = assume_yes;
+    }
+
     /// Runs a command by name with the given arguments.
     ///
+    /// A global `--format <format>` (or `--format=<format>`) argument is removed from the
+    /// arguments and overrides the output format while the command runs. For destructive
+    /// commands, `--yes` (or `-y`) is removed too, and skips confirmation. Neither is removed
+    /// for commands that take other commands as arguments, such as `alias`.
+    ///
     /// # Arguments
     ///
     /// * `command_name` - The name of the command to run.
@@ -57,13 +305,36 @@ impl Dispatch {
     ///
     /// Returns an error string if the command is not found or if the command handler fails.
     pub fn run(&mut self, command_name: &str, args: &[String]) -> Result<()> {
-        match self.commands.iter().find(|c| c.name == command_name) {
-            Some(command) => (command.run)(self, args),
-            None => anyhow::bail!("Command '{}' not found", command_name),
+        let Some(command) = self.command(command_name) else {
+            anyhow::bail!("Command '{}' not found", command_name);
+        };
+        // Refuse piped bookmarks before the command has done anything with the whole store
+        if self.input.is_some() && !command.accepts_input {
+            anyhow::bail!("'{}' does not read piped bookmarks", command.name);
         }
+
+        let (format, mut args) = if VERBATIM.contains(&command.name) {
+            (None, args.to_vec())
+        } else {
+            take_format(args)?
+        };
+        let yes = command.destructive && take_yes(&mut args);
+
+        let previous = format.map(|format| std::mem::replace(&mut self.format, format));
+        let assume_yes = self.assume_yes;
+        self.assume_yes |= yes;
+        let result = (command.run)(self, &args);
+        self.assume_yes = assume_yes;
+        if let Some(previous) = previous {
+            self.format = previous;
+        }
+        result
     }
 
-    /// Parses a single line of input, extracts a command and arguments, and runs it.
+    /// Parses a line of input and runs the commands on it.
+    ///
+    /// Commands may be joined by `;`, by `&&` (run the next only if this one succeeded), and by
+    /// `|` (pass the bookmarks this one selects to the next).
     ///
     /// # Arguments
     ///
@@ -73,10 +344,191 @@ impl Dispatch {
     ///
     /// Returns an error string if no command is provided or if command execution fails.
     pub fn run_line(&mut self, line: &str) -> Result<()> {
-        let tokens = shlex::Shlex::new(line).collect::<Vec<_>>();
-        let command = tokens.first().context("No command provided")?;
-        let args = &tokens[1..];
-        self.run(command, args)
+        self.run_chain(line, &[], None, false)
+    }
+
+    /// Returns the bookmark IDs piped in from the previous command, if any.
+    ///
+    /// Only commands that accept input are run with piped bookmarks, and they must take them;
+    /// leaving them is an error.
+    pub fn take_input(&mut self) -> Option<Vec<usize>> {
+        self.input.take()
+    }
+
+    /// Returns `true` if the command's bookmarks go to another command rather than the screen.
+    pub fn is_piped(&self) -> bool {
+        self.piped
+    }
+
+    /// Selects bookmarks as the command's result, to be piped to the next command.
+    pub fn select(&mut self, ids: Vec<usize>) {
+        self.output = Some(ids);
+    }
+
+    /// Returns the IDs of the bookmarks found by the last search, selected by `last`.
+    pub fn last(&self) -> &[usize] {
+        &self.last
+    }
+
+    /// Remembers the IDs of the bookmarks found by a search.
+    pub fn set_last(&mut self, ids: Vec<usize>) {
+        self.last = ids;
+    }
+
+    /// Returns the current collection, or `None` at the top level.
+    pub fn collection(&self) -> Option<&str> {
+        self.collection.as_deref()
+    }
+
+    /// Changes the current collection.
+    pub fn set_collection(&mut self, collection: Option<String>) {
+        self.collection = collection;
+    }
+
+    /// Scopes query terms to the current collection.
+    ///
+    /// `in:` terms are resolved relative to the current collection; without one, the query is
+    /// limited to the current collection.
+    pub fn scoped(&self, terms: &[String]) -> Result<Vec<String>> {
+        let mut scoped = Vec::with_capacity(terms.len() + 1);
+        let mut has_scope = false;
+        for term in terms {
+            match term.strip_prefix("in:") {
+                Some(path) => {
+                    let collection = collection::resolve(self.collection(), path)?;
+                    scoped.push(format!("in:/{}", collection.unwrap_or_default()));
+                    has_scope = true;
+                }
+                None => scoped.push(term.clone()),
+            }
+        }
+        if let (false, Some(collection)) = (has_scope, self.collection()) {
+            scoped.push(format!("in:/{}", collection));
+        }
+        Ok(scoped)
+    }
+
+    /// Shows a preview of what a destructive command will change, and asks whether to go ahead.
+    ///
+    /// With `--yes`, goes ahead without asking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user cannot be asked, because stdin is not a terminal.
+    pub fn confirm(&mut self, preview: &str, question: &str) -> Result<bool> {
+        if self.assume_yes {
+            return Ok(true);
+        }
+        if !self.interactive {
+            anyhow::bail!(
+                "Cannot ask '{}' as input is not a terminal; pass --yes to go ahead",
+                question
+            );
+        }
+
+        print!("{}", preview);
+        let answer = self.editor.readline(&format!("{} [y/N] ", question))?;
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Runs pipelines joined by `;` and `&&`.
+    ///
+    /// `input` goes to the first command and, if `piped`, the selection of the last command is
+    /// left in `self.output`. Extra arguments are appended to the last command.
+    fn run_chain(
+        &mut self,
+        text: &str,
+        extra_args: &[String],
+        mut input: Option<Vec<usize>>,
+        piped: bool,
+    ) -> Result<()> {
+        let pipelines = line::parse(text)?;
+        let count = pipelines.len();
+        let mut status = Ok(());
+
+        for (i, pipeline) in pipelines.iter().enumerate() {
+            match pipeline.connector {
+                Connector::IfSucceeded if status.is_err() => continue,
+                Connector::IfSucceeded => {}
+                // The error of a command followed by `;` is reported, and does not stop the line
+                Connector::Always => {
+                    if let Err(e) = std::mem::replace(&mut status, Ok(())) {
+                        eprintln!("{}", e);
+                    }
+                }
+            }
+
+            let last = i + 1 == count;
+            let extra_args = if last { extra_args } else { &[] };
+            status = self.run_pipeline(&pipeline.stages, extra_args, input.take(), last && piped);
+        }
+
+        status
+    }
+
+    /// Runs commands joined by `|`, passing each one's selection to the next.
+    fn run_pipeline(
+        &mut self,
+        stages: &[&str],
+        extra_args: &[String],
+        mut input: Option<Vec<usize>>,
+        piped: bool,
+    ) -> Result<()> {
+        for (i, stage) in stages.iter().enumerate() {
+            let last = i + 1 == stages.len();
+            let mut tokens =
+                shlex::split(stage).with_context(|| format!("Unbalanced quotes in '{}'", stage))?;
+            if last {
+                tokens.extend_from_slice(extra_args);
+            }
+
+            self.input = input.take();
+            self.piped = !last || piped;
+            self.output = None;
+            let result = self.run_tokens(tokens);
+            let unread = self.input.take().is_some();
+            self.piped = false;
+            result?;
+
+            if unread {
+                anyhow::bail!("'{}' does not read piped bookmarks", stage);
+            }
+            if !last {
+                let output = self.output.take();
+                input =
+                    Some(output.with_context(|| {
+                        format!("'{}' does not select bookmarks to pipe", stage)
+                    })?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a command or alias from its name and arguments.
+    ///
+    /// An alias that refers to itself, directly or through other aliases, runs the command of the
+    /// same name if there is one, and is an error otherwise.
+    fn run_tokens(&mut self, tokens: Vec<String>) -> Result<()> {
+        let (name, args) = tokens.split_first().context("No command provided")?;
+        let Some(body) = self.config.aliases.get(name).cloned() else {
+            return self.run(name, args);
+        };
+
+        if self.expanding.contains(name) {
+            if self.command(name).is_some() {
+                return self.run(name, args);
+            }
+            anyhow::bail!("Alias cycle: {} -> {}", self.expanding.join(" -> "), name);
+        }
+
+        // The alias's commands take the place of this one in any pipeline
+        let input = self.input.take();
+        let piped = self.piped;
+        self.expanding.push(name.clone());
+        let result = self.run_chain(&body, args, input, piped);
+        self.expanding.pop();
+        result
     }
 
     /// Reads a line from standard input (using `rustyline`).
@@ -85,8 +537,18 @@ impl Dispatch {
     ///
     /// Returns any `ReadlineError` that occurs during input reading.
     pub fn take_line(&mut self) -> Result<String, ReadlineError> {
-        // Read a line from the user
-        let line = self.editor.readline("ℂ ")?;
+        // Read a line from the user, showing where they are before the prompt: the store, if
+        // more than one is open, and the collection
+        let store = (!self.stores.is_empty()).then_some(self.store_name.as_str());
+        let prompt = match (store, &self.collection) {
+            (None, None) => self.config.prompt.clone(),
+            (Some(store), None) => format!("{} {}", store, self.config.prompt),
+            (None, Some(collection)) => format!("{} {}", collection, self.config.prompt),
+            (Some(store), Some(collection)) => {
+                format!("{}:{} {}", store, collection, self.config.prompt)
+            }
+        };
+        let line = self.editor.readline(&prompt)?;
 
         // Add the line to the history
         self.editor.add_history_entry(&line)?;
@@ -100,13 +562,40 @@ impl Dispatch {
         println!("Type 'help' for a list of available commands.");
     }
 
+    /// Saves every open store that has a file, merging in changes made to the files elsewhere.
+    ///
+    /// A merge into the current store renumbers its bookmarks, so the last results are
+    /// forgotten.
+    fn autosave(&mut self) {
+        let stores = std::iter::once(&mut self.store).chain(self.stores.values_mut());
+        let mut renumbered = false;
+        for (i, store) in stores.enumerate() {
+            if store.path.is_none() {
+                continue;
+            }
+            match store.save_to_path() {
+                Ok(Some(merged)) => {
+                    println!("{}", merged);
+                    renumbered |= i == 0;
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Autosave failed: {}", e),
+            }
+        }
+        if renumbered {
+            self.set_last(Vec::new());
+        }
+    }
+
     /// Starts the main read-eval-print loop (REPL) for this dispatch.
     ///
     /// # Errors
     ///
     /// Returns an error string if reading from input fails unexpectedly.
     pub fn start(&mut self) -> Result<()> {
-        Dispatch::motd();
+        if self.config.motd {
+            Dispatch::motd();
+        }
 
         loop {
             let line = self.take_line().map_err(|e| anyhow::anyhow!(e))?;
@@ -119,11 +608,44 @@ impl Dispatch {
             // Attempt to parse and run the command
             if let Err(e) = self.run_line(line) {
                 eprintln!("{}", e);
+                continue;
+            }
+
+            if self.config.autosave {
+                self.autosave();
             }
         }
     }
 }
 
+/// Removes `--yes` and `-y` from a command's arguments, returning whether either was there.
+fn take_yes(args: &mut Vec<String>) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != "--yes" && arg != "-y");
+    args.len() != len
+}
+
+/// Splits the global `--format` option off a command's arguments.
+fn take_format(args: &[String]) -> Result<(Option<OutputFormat>, Vec<String>)> {
+    let mut format = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let value = match arg.strip_prefix("--format") {
+            Some("") => iter.next().ok_or_else(|| MissingArgument(arg.clone()))?,
+            Some(value) if value.starts_with('=') => &value[1..],
+            _ => {
+                rest.push(arg.clone());
+                continue;
+            }
+        };
+        format = Some(value.parse()?);
+    }
+
+    Ok((format, rest))
+}
+
 impl Default for Dispatch {
     fn default() -> Self {
         Self::new(Store::default(), Command::all())
@@ -0,0 +1,45 @@
+use super::error::InvalidArguments;
+use super::render::{self, OutputFormat};
+use super::{Command, Dispatch};
+use crate::store::normalize::normalize;
+use crate::sys::pager;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!(InvalidArguments);
+    }
+
+    let store = dispatch.store();
+    let groups = store.duplicates();
+
+    if groups.is_empty() {
+        println!("No duplicate bookmarks");
+        return Ok(());
+    }
+
+    // Headings would get in the way of reading the other formats
+    let headings = matches!(dispatch.format(), OutputFormat::Table | OutputFormat::Plain);
+    let mut out = String::new();
+    for group in groups {
+        let bookmarks: Vec<_> = group.iter().filter_map(|&id| store.get(id)).collect();
+        if let Some(first) = bookmarks.first().filter(|_| headings) {
+            out.push_str(&format!("{}:\n", normalize(first.url.as_str())));
+        }
+        out.push_str(&render::bookmarks(
+            dispatch.format(),
+            dispatch.style(),
+            &bookmarks,
+        ));
+    }
+    pager::page(&out)
+}
+
+inventory::submit!(Command {
+    name: "dupes",
+    description: "List bookmarks that share the same URL",
+    usage: "dupes",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
@@ -1,20 +1,254 @@
+use std::collections::HashSet;
+
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::render;
+use super::selection;
 use super::{Command, Dispatch};
+use crate::store::{
+    Store,
+    bookmark::Bookmark,
+    entry::Entry,
+    query::Query,
+    url::{Url, UrlPolicy},
+};
 use anyhow::{Context, Result};
 
+/// Explains the file opened in the editor.
+const HEADER: &str = "\
+# Edit the bookmarks below, one per line as `#<id> name: url [tags] {attributes}`.
+# Lines without an ID are added, and bookmarks whose lines are deleted are removed.
+# Save an empty file to cancel.
+";
+
+/// The changes made to a set of bookmarks in the editor.
+#[derive(Debug, Default)]
+struct Changes {
+    edited: Vec<(usize, Bookmark)>,
+    removed: Vec<usize>,
+    added: Vec<Bookmark>,
+}
+
+/// Writes bookmarks in the line format, each prefixed with its ID.
+fn dump(store: &Store, ids: &[usize]) -> String {
+    let mut text = String::from(HEADER);
+    for bookmark in ids.iter().filter_map(|&id| store.get(id)) {
+        let entry = Entry::Bookmark(Box::new(bookmark.bookmark.clone()));
+        text.push_str(&format!("#{} {}\n", bookmark.id, entry));
+    }
+    text
+}
+
+/// Splits a `#<id> ` prefix off a line.
+///
+/// Bookmark names cannot start with an unescaped '#', so the prefix is never part of a name.
+fn split_id(line: &str) -> (Option<usize>, &str) {
+    line.strip_prefix('#')
+        .and_then(|line| line.split_once(' '))
+        .filter(|(id, _)| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|(id, rest)| Some((Some(id.parse().ok()?), rest)))
+        .unwrap_or((None, line))
+}
+
+/// Returns `true` for comment lines, which start with a '#' that is not an ID prefix.
+fn is_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('#') && split_id(line).0.is_none()
+}
+
+/// Parses the edited text and compares it to the bookmarks that were being edited.
+///
+/// Returns every line that failed to parse, with its line number, if any did.
+fn diff(
+    store: &Store,
+    ids: &[usize],
+    text: &str,
+    policy: UrlPolicy,
+) -> Result<Changes, Vec<String>> {
+    let mut changes = Changes::default();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || is_comment(line) {
+            continue;
+        }
+
+        let (id, rest) = split_id(line);
+        let mut bookmark = match Entry::parse(rest, policy).map(Entry::into_bookmark) {
+            Ok(Some(bookmark)) => bookmark,
+            Ok(None) => continue,
+            Err(e) => {
+                errors.push(format!("line {}: {}", i + 1, e));
+                continue;
+            }
+        };
+
+        match id {
+            None => changes.added.push(bookmark),
+            Some(id) if !ids.contains(&id) => {
+                errors.push(format!(
+                    "line {}: bookmark {} is not being edited",
+                    i + 1,
+                    id
+                ));
+            }
+            Some(id) if !seen.insert(id) => {
+                errors.push(format!("line {}: bookmark {} appears twice", i + 1, id));
+            }
+            Some(id) => {
+                // The collection is not part of the line, so it stays as it was
+                bookmark.collection = store.get(id).and_then(|b| b.collection.clone());
+                let original = store.get(id).map(|original| original.line());
+                if original.is_some_and(|original| original != bookmark.line()) {
+                    changes.edited.push((id, bookmark));
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    changes.removed = ids
+        .iter()
+        .copied()
+        .filter(|id| !seen.contains(id))
+        .collect();
+    Ok(changes)
+}
+
+/// Applies changes to the store; removals go last-first so that earlier IDs stay valid.
+fn apply(store: &mut Store, mut changes: Changes) {
+    for (id, bookmark) in changes.edited {
+        store.replace(id, bookmark);
+    }
+    changes.removed.sort_unstable();
+    for &id in changes.removed.iter().rev() {
+        store.remove(id);
+    }
+    for bookmark in changes.added {
+        store.add(bookmark);
+    }
+}
+
+/// Edits bookmarks in `$EDITOR`, re-opening it until the result parses.
+fn edit_in_editor(dispatch: &mut Dispatch, ids: &[usize]) -> Result<()> {
+    let store = dispatch.store();
+    let editor = dispatch.text_editor();
+    let mut text = dump(store, ids);
+
+    let mut changes = loop {
+        let edited = editor.edit(&text, "inno")?;
+        if edited.trim().is_empty() {
+            println!("Edit cancelled");
+            return Ok(());
+        }
+
+        match diff(store, ids, &edited, store.url_policy) {
+            Ok(changes) => break changes,
+            Err(errors) => {
+                for error in &errors {
+                    eprintln!("Error: {}", error);
+                }
+                // Show the errors above what was written, dropping earlier comments
+                text = String::from(HEADER);
+                for error in errors {
+                    text.push_str(&format!("# Error: {}\n", error));
+                }
+                for line in edited.lines().filter(|line| !is_comment(line)) {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+            }
+        }
+    };
+
+    if !changes.removed.is_empty() {
+        let store = dispatch.store();
+        let removed: Vec<_> = changes
+            .removed
+            .iter()
+            .filter_map(|&id| store.get(id))
+            .collect();
+        let preview = render::bookmarks(dispatch.format(), dispatch.style(), &removed);
+        let question = match removed.len() {
+            1 => "Remove this bookmark, whose line was deleted?".to_string(),
+            n => format!("Remove these {} bookmarks, whose lines were deleted?", n),
+        };
+        if !dispatch.confirm(&preview, &question)? {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    println!(
+        "Edited {}, removed {} and added {} bookmarks",
+        changes.edited.len(),
+        changes.removed.len(),
+        changes.added.len()
+    );
+    if !changes.removed.is_empty() {
+        dispatch.set_last(Vec::new());
+    }
+    for bookmark in &mut changes.added {
+        bookmark.collection = dispatch.collection().map(str::to_string);
+    }
+    apply(dispatch.store_mut(), changes);
+
+    Ok(())
+}
+
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [id, new_name, new_url, new_tags @ ..] => {
-            let id = id.parse().context("Invalid ID")?;
-            let mut bookmark = dispatch
-                .store_mut()
-                .get_mut(id)
-                .context("Bookmark not found")?;
+    let args = Args::parse(args, &["all"], &["query"])?;
+
+    if args.flag("all") {
+        let ids: Vec<usize> = dispatch.store().iter().map(|b| b.id).collect();
+        return edit_in_editor(dispatch, &ids);
+    }
+    if let Some(query) = args.value("query") {
+        let query: Query = query.parse()?;
+        let ids: Vec<usize> = dispatch
+            .store()
+            .query(&query)
+            .iter()
+            .map(|b| b.id)
+            .collect();
+        if ids.is_empty() {
+            anyhow::bail!("No bookmarks match the query");
+        }
+        return edit_in_editor(dispatch, &ids);
+    }
+
+    match args.positional() {
+        selections if selection::is_selection(selections) => {
+            let ids = dispatch.selection(selections)?;
+            if ids.is_empty() {
+                anyhow::bail!("No bookmarks selected");
+            }
+            edit_in_editor(dispatch, &ids)?;
+        }
+        [selection, new_name, new_url, new_tags @ ..] => {
+            let id = dispatch.selected_one(selection)?;
+            let new_url = Url::with_policy(new_url, dispatch.store().url_policy)?;
+            if !new_url.is_valid() {
+                eprintln!("Warning: '{}' is not a valid URL", new_url);
+            }
+            let store = dispatch.store_mut();
+            let mut bookmark = store
+                .get(id)
+                .context("Bookmark not found")?
+                .bookmark
+                .clone();
             bookmark.name.clone_from(new_name);
-            bookmark.url.clone_from(new_url);
+            bookmark.url = new_url;
             bookmark.tags = new_tags.to_vec();
+            store.replace(id, bookmark);
             println!("Edited bookmark with ID {}", id);
         }
-        _ => anyhow::bail!("Invalid arguments"),
+        _ => anyhow::bail!(InvalidArguments),
     }
 
     Ok(())
@@ -22,7 +256,85 @@ pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
 
 inventory::submit!(Command {
     name: "edit",
-    description: "Edit an existing bookmark",
-    usage: "edit <id> <new_name> [new_url] [new_tags...]",
+    description: "Edit bookmarks, in $EDITOR unless the new values are given",
+    usage: "edit <selection...> | edit <id> <new_name> <new_url> [new_tags...] | edit --all | edit --query <query> [--yes]",
+    destructive: true,
+    accepts_input: false,
     run,
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_diff_and_apply() {
+        let mut store = Store::new();
+        for name in ["a", "b", "c"] {
+            store.create(
+                name.to_string(),
+                format!("https://example.com/{}", name).into(),
+                vec!["old".to_string()],
+            );
+        }
+        let ids = [0, 1, 2];
+        let text = dump(&store, &ids);
+
+        // Rename "a", retag "c", remove "b" and add "d"
+        let edited = text
+            .replace("#0 a:", "#0 a2:")
+            .replace("/c [old]", "/c [new]")
+            .lines()
+            .filter(|line| !line.starts_with("#1 "))
+            .chain(["2. Chapter two: https://example.com/d [new]"])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let changes = diff(&store, &ids, &edited, UrlPolicy::Prefix).unwrap();
+        assert_eq!(changes.edited.len(), 2);
+        assert_eq!(changes.removed, vec![1]);
+        assert_eq!(changes.added.len(), 1);
+
+        apply(&mut store, changes);
+        let names: Vec<_> = store.iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["a2", "c", "2. Chapter two"]);
+        assert!(store.find_by_tag("old").iter().all(|b| b.name == "a2"));
+        assert_eq!(store.find_by_tag("new").len(), 2);
+    }
+
+    #[test]
+    fn test_removal_needs_confirmation() {
+        let mut dispatch = Dispatch::default();
+        dispatch.set_interactive(false);
+        let config = Config {
+            editor: Some("sed -i /^#1/d".to_string()),
+            ..Config::default()
+        };
+        dispatch.set_config(config).unwrap();
+        for name in ["a", "b"] {
+            dispatch
+                .run_line(&format!("add --offline {0} https://{0}.example", name))
+                .unwrap();
+        }
+
+        assert!(dispatch.run_line("edit --all").is_err());
+        assert_eq!(dispatch.store().bookmark_count(), 2);
+        dispatch.run_line("edit --all --yes").unwrap();
+        assert_eq!(dispatch.store().bookmark_count(), 1);
+    }
+
+    #[test]
+    fn test_diff_errors() {
+        let store = Store::new();
+        let errors = diff(
+            &store,
+            &[],
+            "no separator here\n#5 x: https://example.com []",
+            UrlPolicy::Prefix,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("line 1:"));
+        assert!(errors[1].contains("not being edited"));
+    }
+}
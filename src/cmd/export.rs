@@ -0,0 +1,58 @@
+use super::args::Args;
+use super::error::{InvalidArgument, InvalidArguments};
+use super::{Command, Dispatch};
+use crate::store::export::{self, MarkdownOptions};
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    match args {
+        [format, rest @ ..] if format == "markdown" => markdown(dispatch, rest),
+        [format, ..] => anyhow::bail!(InvalidArgument(format.clone())),
+        _ => anyhow::bail!(InvalidArguments),
+    }
+}
+
+fn markdown(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(
+        args,
+        &["untagged", "nested", "no-comments"],
+        &["sort", "title"],
+    )?;
+
+    let mut options = MarkdownOptions {
+        untagged: args.flag("untagged"),
+        nested: args.flag("nested"),
+        comments: !args.flag("no-comments"),
+        selection: dispatch.take_input(),
+        ..Default::default()
+    };
+    if let Some(sort) = args.value("sort") {
+        options.sort = sort.parse()?;
+    }
+    if let Some(title) = args.value("title") {
+        options.title = title.to_string();
+    }
+
+    let document = export::markdown(dispatch.store(), &options);
+
+    match args.positional() {
+        [] => print!("{}", document),
+        [path] => {
+            std::fs::write(path, document)
+                .with_context(|| format!("Failed to write '{}'", path))?;
+            println!("Exported bookmarks to {}", path);
+        }
+        _ => anyhow::bail!(InvalidArguments),
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "export",
+    description: "Export bookmarks to another format",
+    usage: "export markdown [path] [--sort store|name|url|added|modified|visited] [--untagged] [--nested] [--no-comments] [--title <title>]",
+    destructive: false,
+    accepts_input: true,
+    run,
+});
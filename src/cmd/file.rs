@@ -0,0 +1,95 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use crate::store::collection;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    // The collection comes last, after the bookmarks if they are not piped in
+    let (path, selections) = args.split_last().ok_or(InvalidArguments)?;
+    let ids = match dispatch.take_input() {
+        Some(input) if selections.is_empty() => input,
+        None if !selections.is_empty() => dispatch.selection(selections)?,
+        _ => anyhow::bail!(InvalidArguments),
+    };
+    let collection = collection::resolve(dispatch.collection(), path)?;
+
+    let mut moved = 0;
+    for &id in &ids {
+        if dispatch.store_mut().set_collection(id, collection.clone()) {
+            moved += 1;
+        }
+    }
+
+    if !dispatch.is_piped() {
+        println!(
+            "Filed {} bookmarks under {}",
+            moved,
+            collection.as_deref().unwrap_or("the top level")
+        );
+    }
+    dispatch.select(ids);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "file",
+    description: "File bookmarks under a collection",
+    usage: "file <selection...> <collection|..|/> (or piped bookmarks: ... | file <collection>)",
+    destructive: false,
+    accepts_input: true,
+    run,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collections() {
+        let mut dispatch = Dispatch::default();
+        for line in [
+            "add --offline Rust https://www.rust-lang.org rust",
+            "mkdir Work/Rust",
+            "cd Work",
+            "add --offline Cargo https://doc.rust-lang.org/cargo rust",
+            "find tag:rust | file Rust",
+            "add --offline Jira https://jira.example",
+        ] {
+            dispatch.run_line(line).unwrap();
+        }
+
+        let collections: Vec<_> = dispatch
+            .store()
+            .iter()
+            .map(|b| b.collection.clone())
+            .collect();
+        assert_eq!(
+            collections,
+            vec![
+                Some("Work/Rust".to_string()),
+                Some("Work/Rust".to_string()),
+                Some("Work".to_string())
+            ]
+        );
+
+        // Queries are scoped to the current collection unless they say otherwise
+        let scoped = |dispatch: &Dispatch, terms: &[&str]| {
+            let terms: Vec<String> = terms.iter().map(ToString::to_string).collect();
+            dispatch.scoped(&terms).unwrap()
+        };
+        assert_eq!(scoped(&dispatch, &["jira"]), vec!["jira", "in:/Work"]);
+        assert_eq!(scoped(&dispatch, &["in:Rust"]), vec!["in:/Work/Rust"]);
+        assert_eq!(scoped(&dispatch, &["in:/"]), vec!["in:/"]);
+
+        dispatch.run_line("cd Rust; file 0 ..").unwrap();
+        assert_eq!(dispatch.collection(), Some("Work/Rust"));
+        assert_eq!(
+            dispatch.store().get(0).unwrap().collection.as_deref(),
+            Some("Work")
+        );
+        assert!(dispatch.run_line("cd Nowhere").is_err());
+        dispatch.run_line("cd").unwrap();
+        assert_eq!(dispatch.collection(), None);
+    }
+}
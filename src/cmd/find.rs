@@ -1,23 +1,72 @@
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::listing::{self, Listing};
+use super::render;
 use super::{Command, Dispatch};
+use crate::store::query::Query;
+use crate::sys::pager;
 use anyhow::Result;
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [query] => {
-            let bookmarks = dispatch.store().find(query);
-            for bookmark in bookmarks {
-                println!("{}", bookmark);
-            }
-        }
-        _ => anyhow::bail!("Invalid arguments"),
+    let switches = [&["all-stores"], listing::SWITCHES].concat();
+    let args = Args::parse(args, &switches, listing::OPTIONS)?;
+    if args.flag("all-stores") {
+        return find_everywhere(dispatch, &args);
+    }
+
+    let input = dispatch.take_input();
+    if args.positional().is_empty() && input.is_none() {
+        anyhow::bail!(InvalidArguments);
     }
 
+    // Piped bookmarks narrow the search
+    let query = Query::parse(args.positional())?;
+    let ids: Vec<usize> = dispatch
+        .store()
+        .query(&query)
+        .iter()
+        .map(|b| b.id)
+        .filter(|id| input.as_ref().is_none_or(|input| input.contains(id)))
+        .collect();
+
+    let shown = Listing::from_args(&args)?.show(dispatch, &ids)?;
+    dispatch.set_last(shown);
     Ok(())
 }
 
+/// Searches every open store, listing the results under each store's name.
+///
+/// IDs only mean something within a store, so the results are not remembered or piped.
+fn find_everywhere(dispatch: &mut Dispatch, args: &Args) -> Result<()> {
+    if dispatch.take_input().is_some() || dispatch.is_piped() {
+        anyhow::bail!("Results from several stores cannot be piped");
+    }
+    if args.positional().is_empty() {
+        anyhow::bail!(InvalidArguments);
+    }
+
+    let query = Query::parse(args.positional())?;
+    let listing = Listing::from_args(args)?;
+    let mut out = String::new();
+    for (name, store) in dispatch.stores() {
+        let bookmarks = listing.apply(store.query(&query));
+        if !bookmarks.is_empty() {
+            out.push_str(&format!("{}:\n", name));
+            out.push_str(&render::bookmarks(
+                dispatch.format(),
+                dispatch.style(),
+                &bookmarks,
+            ));
+        }
+    }
+    pager::page(&out)
+}
+
 inventory::submit!(Command {
     name: "find",
-    description: "Find bookmarks by name or tag",
-    usage: "find <query>",
+    description: "Find bookmarks by name, URL, tag or date",
+    usage: "find <terms...> [--all-stores] [--sort name|url|added|visits|tag|...] [--reverse] [--limit n] [--offset n]",
+    destructive: false,
+    accepts_input: true,
     run,
 });
@@ -8,10 +8,37 @@ pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
             for command in dispatch.commands() {
                 println!("  {:<10} {}", command.name, command.description);
             }
+            let aliases = &dispatch.config().aliases;
+            if !aliases.is_empty() {
+                println!();
+                println!("Aliases:");
+                for (name, body) in aliases {
+                    println!("  {:<10} {}", name, body);
+                }
+            }
+            println!();
+            println!("Listing commands accept --format table|plain|json|jsonl|tsv|<template>.");
+            println!(
+                "Join commands with ';', '&&' (if the last succeeded) or '|' (pass on its bookmarks)."
+            );
+            println!(
+                "Bookmarks are selected by ID (3), range (3-9), list (1,4,7), 'last' (the last \
+                 find) or query (q:tag:old)."
+            );
+            println!(
+                "Bookmarks can be filed under collections such as Work/Rust; see cd, ls, mkdir and \
+                 file."
+            );
+            println!("Several stores can be open at once; see store, use, move and copy --to.");
         }
         [name] => {
-            if let Some(command) = dispatch.command(name) {
+            if let Some(body) = dispatch.config().aliases.get(name) {
+                println!("{} is an alias for: {}", name, body);
+            } else if let Some(command) = dispatch.command(name) {
                 println!("Usage: {} {}", command.name, command.usage);
+                if command.destructive {
+                    println!("Asks for confirmation first; pass --yes to skip it.");
+                }
             } else {
                 eprintln!("Unknown command: {}", name);
             }
@@ -26,5 +53,7 @@ inventory::submit!(Command {
     name: "help",
     description: "Show help for a command or list all commands",
     usage: "help [command]",
+    destructive: false,
+    accepts_input: false,
     run,
 });
@@ -0,0 +1,47 @@
+use super::error::{InvalidArgument, InvalidArguments};
+use super::{Command, Dispatch};
+use crate::store::import::Format;
+use anyhow::Result;
+use std::path::Path;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    match args {
+        [] => {
+            println!("Available formats:");
+            for format in Format::all() {
+                println!("  {:<10} {}", format.name, format.description);
+            }
+        }
+        [format, path] => {
+            let format = Format::find(format).ok_or_else(|| InvalidArgument(format.clone()))?;
+            let bookmarks = (format.read)(Path::new(path))?;
+            let report = dispatch.store_mut().import(bookmarks);
+
+            println!("Imported {} from {}", report, path);
+            for merge in &report.merged {
+                if let Some(bookmark) = dispatch.store().get(merge.id) {
+                    println!(
+                        "  merged into {} (+{})",
+                        bookmark,
+                        merge.new_tags.join(", ")
+                    );
+                }
+            }
+            for url in &report.rejected {
+                eprintln!("Warning: skipped invalid URL '{}'", url);
+            }
+        }
+        _ => anyhow::bail!(InvalidArguments),
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "import",
+    description: "Import bookmarks from another application",
+    usage: "import [<format> <path>]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
@@ -0,0 +1,159 @@
+use thiserror::Error;
+
+/// How a pipeline is joined to the one before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connector {
+    /// `;`: run regardless of how the previous pipeline went.
+    Always,
+    /// `&&`: run only if the previous pipeline succeeded.
+    IfSucceeded,
+}
+
+/// Commands joined by `|`, each receiving the bookmarks selected by the one before.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pipeline<'a> {
+    pub connector: Connector,
+    pub stages: Vec<&'a str>,
+}
+
+#[derive(Error, Debug)]
+pub enum LineError {
+    #[error("Missing command before or after '{0}'")]
+    EmptyCommand(&'static str),
+    #[error("'||' is not supported")]
+    Or,
+}
+
+/// Adds a stage ended by `operator` to a pipeline.
+///
+/// An empty stage is only allowed where `;` separates nothing, as in `show;` or `;;`.
+fn push_stage<'a>(
+    pipeline: &mut Pipeline<'a>,
+    stage: &'a str,
+    operator: &'static str,
+) -> Result<(), LineError> {
+    let stage = stage.trim();
+    if !stage.is_empty() {
+        pipeline.stages.push(stage);
+        return Ok(());
+    }
+
+    let nothing_pending = pipeline.stages.is_empty() && pipeline.connector == Connector::Always;
+    if operator == ";" && nothing_pending {
+        Ok(())
+    } else {
+        Err(LineError::EmptyCommand(operator))
+    }
+}
+
+/// Splits a line into pipelines at each `;`, `&&` and `|` that is not quoted or escaped.
+///
+/// Quoting follows the same rules as argument splitting: nothing is special inside single
+/// quotes, and a backslash escapes the next character elsewhere.
+pub fn parse(line: &str) -> Result<Vec<Pipeline<'_>>, LineError> {
+    let mut pipelines = Vec::new();
+    let mut current = Pipeline {
+        connector: Connector::Always,
+        stages: Vec::new(),
+    };
+    let mut start = 0;
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let operator = match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => {
+                quote = None;
+                continue;
+            }
+            (Some('\''), _) => continue,
+            (_, '\\') => {
+                chars.next();
+                continue;
+            }
+            (Some(_), _) => continue,
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                continue;
+            }
+            (None, ';') => ";",
+            (None, '|') if chars.next_if(|&(_, c)| c == '|').is_some() => {
+                return Err(LineError::Or);
+            }
+            (None, '|') => "|",
+            (None, '&') if chars.next_if(|&(_, c)| c == '&').is_some() => "&&",
+            _ => continue,
+        };
+
+        push_stage(&mut current, &line[start..i], operator)?;
+        start = i + operator.len();
+        if operator == "|" {
+            continue;
+        }
+
+        let connector = if operator == "&&" {
+            Connector::IfSucceeded
+        } else {
+            Connector::Always
+        };
+        let finished = std::mem::replace(
+            &mut current,
+            Pipeline {
+                connector,
+                stages: Vec::new(),
+            },
+        );
+        if !finished.stages.is_empty() {
+            pipelines.push(finished);
+        }
+    }
+
+    let rest = line[start..].trim();
+    if rest.is_empty() && !current.stages.is_empty() {
+        return Err(LineError::EmptyCommand("|"));
+    }
+    if rest.is_empty() && current.connector == Connector::IfSucceeded {
+        return Err(LineError::EmptyCommand("&&"));
+    }
+    if !rest.is_empty() {
+        current.stages.push(rest);
+        pipelines.push(current);
+    }
+
+    Ok(pipelines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let pipelines =
+            parse(r#"find tag:rust | tag add "a|b" && export markdown 'x;y.md'; status x\;y;"#)
+                .unwrap();
+        assert_eq!(
+            pipelines,
+            vec![
+                Pipeline {
+                    connector: Connector::Always,
+                    stages: vec!["find tag:rust", r#"tag add "a|b""#],
+                },
+                Pipeline {
+                    connector: Connector::IfSucceeded,
+                    stages: vec!["export markdown 'x;y.md'"],
+                },
+                Pipeline {
+                    connector: Connector::Always,
+                    stages: vec![r"status x\;y"],
+                },
+            ]
+        );
+
+        assert!(parse(" ; ").unwrap().is_empty());
+        assert!(parse("find x |").is_err());
+        assert!(parse("| show").is_err());
+        assert!(parse("show &&").is_err());
+        assert!(parse("show || find x").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use super::Dispatch;
+use super::args::Args;
+use super::render;
+use crate::store::{query::SortOrder, refs::BookmarkRef};
+use crate::sys::pager;
+use anyhow::{Context, Result};
+
+/// Switches accepted by every command that lists bookmarks.
+pub const SWITCHES: &[&str] = &["reverse"];
+
+/// Options accepted by every command that lists bookmarks.
+pub const OPTIONS: &[&str] = &["sort", "limit", "offset"];
+
+/// How to order and page a list of bookmarks.
+#[derive(Default)]
+pub struct Listing {
+    sort: Option<SortOrder>,
+    reverse: bool,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl Listing {
+    /// Reads `--sort`, `--reverse`, `--limit` and `--offset`.
+    pub fn from_args(args: &Args) -> Result<Self> {
+        Ok(Self {
+            sort: args.value("sort").map(str::parse).transpose()?,
+            reverse: args.flag("reverse"),
+            limit: args
+                .value("limit")
+                .map(str::parse)
+                .transpose()
+                .context("Invalid limit")?,
+            offset: args
+                .value("offset")
+                .map(str::parse)
+                .transpose()
+                .context("Invalid offset")?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Sorts and pages bookmarks; without `--sort`, their order is kept.
+    pub fn apply<'a>(&self, mut bookmarks: Vec<BookmarkRef<'a>>) -> Vec<BookmarkRef<'a>> {
+        if let Some(sort) = self.sort {
+            sort.sort(&mut bookmarks);
+        }
+        if self.reverse {
+            bookmarks.reverse();
+        }
+        bookmarks
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Prints bookmarks in order, through the pager if there are many, and selects them.
+    ///
+    /// When the command is piped, the bookmarks go to the next command instead of the screen.
+    /// Returns the IDs of the bookmarks shown.
+    pub fn show(&self, dispatch: &mut Dispatch, ids: &[usize]) -> Result<Vec<usize>> {
+        let store = dispatch.store();
+        let bookmarks = self.apply(ids.iter().filter_map(|&id| store.get(id)).collect());
+        let text = (!dispatch.is_piped())
+            .then(|| render::bookmarks(dispatch.format(), dispatch.style(), &bookmarks));
+        let ids: Vec<usize> = bookmarks.iter().map(|b| b.id).collect();
+
+        dispatch.select(ids.clone());
+        if let Some(text) = text {
+            pager::page(&text)?;
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+
+    #[test]
+    fn test_apply() {
+        let mut store = Store::new();
+        for name in ["b", "d", "a", "c"] {
+            store.create(
+                name.to_string(),
+                format!("https://example.com/{}", name).into(),
+                vec![],
+            );
+        }
+
+        let args: Vec<String> = [
+            "--sort",
+            "name",
+            "--reverse",
+            "--offset",
+            "1",
+            "--limit",
+            "2",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let args = Args::parse(&args, SWITCHES, OPTIONS).unwrap();
+        let listing = Listing::from_args(&args).unwrap();
+
+        let names: Vec<_> = listing
+            .apply(store.iter().collect())
+            .iter()
+            .map(|b| b.name.clone())
+            .collect();
+        assert_eq!(names, vec!["c", "b"]);
+    }
+}
@@ -1,16 +1,53 @@
 use super::{Command, Dispatch};
+use crate::store::Store;
 use anyhow::Result;
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [path] => dispatch.store_mut().load(path),
-        _ => anyhow::bail!("Invalid arguments"),
+    let [path] = args else {
+        anyhow::bail!("Invalid arguments");
+    };
+
+    // Loading adds to the bookmarks already in the store, so check before doing so
+    if dispatch.store().bookmark_count() > 0 {
+        let mut loaded = Store::new();
+        loaded.load(path)?;
+
+        let store = dispatch.store();
+        let duplicates = loaded
+            .iter()
+            .filter(|bookmark| store.find_url(bookmark.url.as_str()).is_some())
+            .count();
+        let preview = format!(
+            "The store already has {} bookmarks; {} more will be added from {}, {} of them with URLs \
+             already in the store.\n",
+            store.bookmark_count(),
+            loaded.bookmark_count(),
+            path,
+            duplicates
+        );
+        if !dispatch.confirm(&preview, "Load them anyway?")? {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    let existing = dispatch.store().bookmark_count();
+    dispatch.store_mut().load(path)?;
+
+    for bookmark in dispatch.store().iter().skip(existing) {
+        if !bookmark.url.is_valid() {
+            eprintln!("Warning: bookmark {} has an invalid URL", bookmark);
+        }
     }
+
+    Ok(())
 }
 
 inventory::submit!(Command {
     name: "load",
     description: "Load bookmarks from a file",
-    usage: "load <path>",
+    usage: "load <path> [--yes]",
+    destructive: true,
+    accepts_input: false,
     run,
 });
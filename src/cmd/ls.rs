@@ -0,0 +1,55 @@
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::listing::{self, Listing};
+use super::{Command, Dispatch};
+use crate::store::collection;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, listing::SWITCHES, listing::OPTIONS)?;
+    let current = match args.positional() {
+        [] => dispatch.collection().map(str::to_string),
+        [path] => collection::resolve(dispatch.collection(), path)?,
+        _ => anyhow::bail!(InvalidArguments),
+    };
+
+    let store = dispatch.store();
+    if let Some(current) = &current
+        && !store.collections().contains(current)
+    {
+        anyhow::bail!("Collection '{}' not found", current);
+    }
+
+    // Collections directly below this one, with how many bookmarks they hold in all
+    if !dispatch.is_piped() {
+        for child in store
+            .collections()
+            .iter()
+            .filter(|child| collection::parent(child) == current.as_deref())
+        {
+            let count = store
+                .iter()
+                .filter(|b| collection::within(b.collection.as_deref(), Some(child)))
+                .count();
+            println!("{}/ ({})", collection::name(child), count);
+        }
+    }
+
+    let ids: Vec<usize> = store
+        .iter()
+        .filter(|b| b.collection == current)
+        .map(|b| b.id)
+        .collect();
+    Listing::from_args(&args)?.show(dispatch, &ids)?;
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "ls",
+    description: "List the collections and bookmarks in a collection",
+    usage: "ls [collection] [--sort name|url|added|visits|tag|...] [--reverse] [--limit n] [--offset n]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
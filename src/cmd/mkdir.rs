@@ -0,0 +1,29 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use crate::store::collection;
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let [path] = args else {
+        anyhow::bail!(InvalidArguments);
+    };
+    let collection =
+        collection::resolve(dispatch.collection(), path)?.context("The top level always exists")?;
+
+    if dispatch.store_mut().add_collection(&collection) {
+        println!("Created collection {}", collection);
+    } else {
+        println!("Collection {} already exists", collection);
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "mkdir",
+    description: "Create an empty collection",
+    usage: "mkdir <collection>",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
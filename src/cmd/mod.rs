@@ -1,17 +1,44 @@
 use anyhow::Result;
 mod dispatch;
 pub use dispatch::Dispatch;
+mod args;
 mod error;
 
 mod add;
+mod alias;
+mod archive;
+mod cd;
+mod check;
+mod config;
+mod copy;
+mod dedupe;
+mod dupes;
 mod edit;
+mod export;
+mod file;
 mod find;
 mod help;
+mod import;
+mod line;
+mod listing;
 mod load;
+mod ls;
+mod mkdir;
+mod note;
+mod open;
 mod remove;
+mod render;
+mod resolve;
 mod save;
+mod selection;
 mod show;
 mod status;
+mod store;
+mod table;
+mod tag;
+mod template;
+mod transfer;
+mod use_store;
 
 /// Represents a user-invoked command within the application.
 /// Each command includes a name, description, usage instructions, and a run function.
@@ -22,6 +49,10 @@ pub struct Command {
     pub description: &'static str,
     /// Usage instructions for the command (e.g., "add <item>").
     pub usage: &'static str,
+    /// Whether the command may discard data, and so asks for confirmation unless given `--yes`.
+    pub destructive: bool,
+    /// Whether the command reads bookmarks piped into it.
+    pub accepts_input: bool,
     /// The function to execute when the command is invoked.
     pub run: fn(&mut Dispatch, &[String]) -> Result<()>,
 }
@@ -0,0 +1,54 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let (id, text) = match args {
+        [id] => (id, None),
+        [id, text @ ..] => (id, Some(text.join(" "))),
+        [] => anyhow::bail!(InvalidArguments),
+    };
+    let id = dispatch.selected_one(id)?;
+    let current = dispatch
+        .store()
+        .get(id)
+        .context("Bookmark not found")?
+        .description
+        .clone();
+
+    let note = match text {
+        Some(text) => text,
+        None => dispatch
+            .text_editor()
+            .edit(current.as_deref().unwrap_or_default(), "md")?,
+    };
+
+    // Trailing newlines are an artifact of editing, not part of the note
+    let note = note.trim_end();
+    let note = (!note.is_empty()).then(|| note.to_string());
+    if note == current {
+        println!("Note unchanged");
+        return Ok(());
+    }
+
+    let mut bookmark = dispatch
+        .store_mut()
+        .get_mut(id)
+        .context("Bookmark not found")?;
+    match note {
+        Some(_) => println!("Updated note on bookmark {}", id),
+        None => println!("Removed note from bookmark {}", id),
+    }
+    bookmark.description = note;
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "note",
+    description: "Write a note about a bookmark, in $EDITOR unless given",
+    usage: "note <selection> [text...]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
@@ -0,0 +1,65 @@
+use super::args::Args;
+use super::error::InvalidArguments;
+use super::selection;
+use super::{Command, Dispatch};
+use crate::store::query::Query;
+use anyhow::{Context, Result};
+
+/// The most bookmarks a query may open without `--all`.
+const MAX_WITHOUT_ALL: usize = 5;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &["all"], &[])?;
+    let input = dispatch.take_input();
+    if args.positional().is_empty() && input.is_none() {
+        anyhow::bail!(InvalidArguments);
+    }
+
+    // Piped bookmarks, a selection, or a query whose results are opened
+    let ids: Vec<usize> = if let Some(input) = input {
+        input
+    } else {
+        match args.positional() {
+            selections if selection::is_selection(selections) => dispatch.selection(selections)?,
+            _ => {
+                let query = args.positional().join(" ");
+                let ids: Vec<usize> = dispatch
+                    .store()
+                    .query(&Query::parse(args.positional())?)
+                    .iter()
+                    .map(|b| b.id)
+                    .collect();
+                if ids.is_empty() {
+                    anyhow::bail!("No bookmarks match '{}'", query);
+                }
+                if ids.len() > MAX_WITHOUT_ALL && !args.flag("all") {
+                    anyhow::bail!(
+                        "'{}' matches {} bookmarks; use --all to open them all",
+                        query,
+                        ids.len()
+                    );
+                }
+                ids
+            }
+        }
+    };
+
+    let opener = dispatch.opener();
+    for id in ids {
+        let bookmark = dispatch.store().get(id).context("Bookmark not found")?;
+        opener.open(bookmark.url.as_str())?;
+        println!("Opened {}", bookmark);
+        dispatch.store_mut().visit(id);
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "open",
+    description: "Open bookmarks in the browser",
+    usage: "open <selection...|query> [--all] (or piped bookmarks)",
+    destructive: false,
+    accepts_input: true,
+    run,
+});
@@ -1,18 +1,44 @@
 use super::error::InvalidArguments;
+use super::render;
 use super::{Command, Dispatch};
 use anyhow::{Context, Result};
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [id] => {
-            let id = id.parse().context("Invalid ID")?;
-            dispatch
-                .store_mut()
-                .remove(id)
-                .context("Bookmark not found")?;
-            println!("Removed bookmark with ID {}", id);
-        }
-        _ => anyhow::bail!(InvalidArguments),
+    let mut ids = match (dispatch.take_input(), args) {
+        (Some(input), []) => input,
+        (None, []) => anyhow::bail!(InvalidArguments),
+        (_, selections) => dispatch.selection(selections)?,
+    };
+    if ids.is_empty() {
+        println!("No bookmarks selected");
+        return Ok(());
+    }
+
+    let store = dispatch.store();
+    let bookmarks: Vec<_> = ids.iter().filter_map(|&id| store.get(id)).collect();
+    let preview = render::bookmarks(dispatch.format(), dispatch.style(), &bookmarks);
+    let question = match ids.len() {
+        1 => "Remove this bookmark?".to_string(),
+        n => format!("Remove these {} bookmarks?", n),
+    };
+    if !dispatch.confirm(&preview, &question)? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    // Removing shifts the IDs after each removed bookmark, so go from the highest down
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    for &id in &ids {
+        dispatch
+            .store_mut()
+            .remove(id)
+            .context("Bookmark not found")?;
+    }
+    dispatch.set_last(Vec::new());
+
+    match ids[..] {
+        [id] => println!("Removed bookmark with ID {}", id),
+        _ => println!("Removed {} bookmarks", ids.len()),
     }
 
     Ok(())
@@ -20,7 +46,36 @@ pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
 
 inventory::submit!(Command {
     name: "remove",
-    description: "Remove a bookmark",
-    usage: "remove <id>",
+    description: "Remove bookmarks",
+    usage: "remove <selection...> (e.g. 3, 3-9, 1,4,7, last or q:tag:dead; or piped bookmarks) [--yes]",
+    destructive: true,
+    accepts_input: true,
     run,
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_needs_confirmation() {
+        let mut dispatch = Dispatch::default();
+        dispatch.set_interactive(false);
+        for name in ["a", "b", "c", "d"] {
+            dispatch
+                .run_line(&format!("add --offline {0} https://{0}.example", name))
+                .unwrap();
+        }
+
+        assert!(dispatch.run_line("remove 0").is_err());
+        assert_eq!(dispatch.store().bookmark_count(), 4);
+
+        dispatch.run_line("remove 0,2 --yes").unwrap();
+        let names: Vec<_> = dispatch.store().iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["b", "d"]);
+
+        dispatch.set_assume_yes(true);
+        dispatch.run_line("remove -y last; remove 1").unwrap();
+        assert_eq!(dispatch.store().bookmark_count(), 1);
+    }
+}
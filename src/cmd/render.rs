@@ -0,0 +1,244 @@
+use std::{fmt::Write, str::FromStr};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use super::table::{Cell, Style, Table};
+use super::template;
+use crate::store::refs::BookmarkRef;
+
+/// How listing commands write their output, chosen with the global `--format` flag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns, with coloured tags on a terminal.
+    #[default]
+    Table,
+    /// One `id. name: url [tags]` line per bookmark.
+    Plain,
+    /// A single JSON array (or object, for summaries).
+    Json,
+    /// One JSON object per line.
+    Jsonl,
+    /// Tab-separated values without a header: id, name, URL and tags.
+    Tsv,
+    /// A template filled in once per bookmark, e.g. `{id}\t{url}`.
+    Template(String),
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown output format: '{0}' (expected table, plain, json, jsonl, tsv or a template)")]
+pub struct UnknownOutputFormat(String);
+
+impl FromStr for OutputFormat {
+    type Err = UnknownOutputFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "plain" => Ok(OutputFormat::Plain),
+            "json" => Ok(OutputFormat::Json),
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            "tsv" => Ok(OutputFormat::Tsv),
+            template if template.contains('{') => Ok(OutputFormat::Template(unescape(template))),
+            _ => Err(UnknownOutputFormat(s.to_string())),
+        }
+    }
+}
+
+/// Interprets `\t`, `\n` and `\\` in a template typed at the prompt.
+fn unescape(template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('t')) => out.push('\t'),
+            ('\\', Some('n')) => out.push('\n'),
+            ('\\', Some('\\')) => out.push('\\'),
+            _ => {
+                out.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    out
+}
+
+/// Returns a bookmark as a JSON object.
+fn bookmark_json(bookmark: &BookmarkRef) -> Value {
+    let time = |time: Option<DateTime<Utc>>| {
+        time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+    };
+    json!({
+        "id": bookmark.id,
+        "name": bookmark.name,
+        "url": bookmark.url.as_str(),
+        "tags": bookmark.tags,
+        "collection": bookmark.collection,
+        "description": bookmark.description,
+        "created": time(bookmark.created),
+        "modified": time(bookmark.modified),
+        "visited": time(bookmark.visited),
+        "visits": bookmark.visits,
+        "status": bookmark.status.map(|status| status.to_string()),
+        "aliases": bookmark.aliases.iter().map(|alias| alias.as_str()).collect::<Vec<_>>(),
+    })
+}
+
+/// Replaces characters that would break a TSV row.
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Renders bookmarks in an output format.
+pub fn bookmarks(format: &OutputFormat, style: &Style, bookmarks: &[BookmarkRef]) -> String {
+    let mut out = String::new();
+    match format {
+        OutputFormat::Table => {
+            // The URL goes last, so that it can be cut short without misaligning anything
+            let mut table =
+                Table::new(style, &["ID", "NAME", "HOST", "TAGS", "URL"]).flexible(&[4, 1, 3]);
+            for b in bookmarks {
+                table.push(vec![
+                    Cell::plain(&b.id.to_string()),
+                    Cell::plain(&b.name),
+                    Cell::plain(&b.url.host().unwrap_or_default()),
+                    style.tags(&b.tags),
+                    Cell::plain(&b.url.to_string()),
+                ]);
+            }
+            out = table.render(style.width);
+        }
+        OutputFormat::Plain => {
+            for bookmark in bookmarks {
+                let _ = writeln!(out, "{}", bookmark);
+            }
+        }
+        OutputFormat::Json => {
+            let array: Vec<Value> = bookmarks.iter().map(bookmark_json).collect();
+            let _ = writeln!(out, "{:#}", Value::Array(array));
+        }
+        OutputFormat::Jsonl => {
+            for bookmark in bookmarks {
+                let _ = writeln!(out, "{}", bookmark_json(bookmark));
+            }
+        }
+        OutputFormat::Tsv => {
+            for b in bookmarks {
+                let _ = writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}",
+                    b.id,
+                    tsv_field(&b.name),
+                    tsv_field(b.url.as_str()),
+                    tsv_field(&b.tags.join(","))
+                );
+            }
+        }
+        OutputFormat::Template(template) => {
+            for bookmark in bookmarks {
+                let _ = writeln!(out, "{}", template::expand(template, bookmark));
+            }
+        }
+    }
+    out
+}
+
+/// Returns a value as text, without quotes around strings.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(value_text).collect::<Vec<_>>().join(", "),
+        value => value.to_string(),
+    }
+}
+
+/// Renders a summary made of named values, such as the output of `status`.
+///
+/// In a table, lists of strings are shown as tags.
+pub fn fields(format: &OutputFormat, style: &Style, fields: &[(&str, Value)]) -> String {
+    let object = || {
+        Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    };
+
+    let mut out = String::new();
+    match format {
+        OutputFormat::Table => {
+            let mut table = Table::new(style, &["FIELD", "VALUE"]).flexible(&[1]);
+            for (key, value) in fields {
+                let value = match value {
+                    Value::Array(items) => {
+                        style.tags(&items.iter().map(value_text).collect::<Vec<_>>())
+                    }
+                    value => Cell::plain(&value_text(value)),
+                };
+                table.push(vec![Cell::plain(key), value]);
+            }
+            out = table.render(style.width);
+        }
+        OutputFormat::Plain => {
+            for (key, value) in fields {
+                let _ = writeln!(out, "{}: {}", key, value_text(value));
+            }
+        }
+        OutputFormat::Json => {
+            let _ = writeln!(out, "{:#}", object());
+        }
+        OutputFormat::Jsonl => {
+            let _ = writeln!(out, "{}", object());
+        }
+        OutputFormat::Tsv => {
+            for (key, value) in fields {
+                let _ = writeln!(out, "{}\t{}", key, tsv_field(&value_text(value)));
+            }
+        }
+        OutputFormat::Template(template) => {
+            let mut line = template.clone();
+            for (key, value) in fields {
+                line = line.replace(&format!("{{{}}}", key), &value_text(value));
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::bookmark::Bookmark;
+
+    #[test]
+    fn test_bookmarks() {
+        let bookmark = Bookmark::new(
+            "Rust\tlang".to_string(),
+            "https://www.rust-lang.org/".into(),
+            vec!["rust".to_string(), "lang".to_string()],
+        );
+        let bookmarks = [BookmarkRef::new(3, &bookmark)];
+        let style = Style::default();
+        let render = |format: &str| super::bookmarks(&format.parse().unwrap(), &style, &bookmarks);
+
+        assert_eq!(
+            render("tsv"),
+            "3\tRust lang\thttps://www.rust-lang.org/\trust,lang\n"
+        );
+        assert_eq!(render(r"{id}\t{url}"), "3\thttps://www.rust-lang.org/\n");
+        assert_eq!(
+            render("table"),
+            "ID  NAME       HOST               TAGS        URL\n\
+             3   Rust lang  www.rust-lang.org  rust, lang  https://www.rust-lang.org/\n"
+        );
+
+        let line: Value = serde_json::from_str(&render("jsonl")).unwrap();
+        assert_eq!(line["id"], 3);
+        assert_eq!(line["tags"], json!(["rust", "lang"]));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use super::args::Args;
+use super::check::Targets;
+use super::{Command, Dispatch};
+use crate::net::{self, check};
+use crate::store::url::Url;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &["rewrite"], &["jobs"])?;
+    let targets = Targets::from_args(dispatch, &args)?;
+
+    let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+    let mut results = check::check_all(&agent, targets.urls, targets.jobs);
+    results.sort_by_key(|(id, _)| *id);
+
+    let mut moved = 0;
+    for (id, result) in results {
+        let Some(mut bookmark) = dispatch.store_mut().get_mut(id) else {
+            continue;
+        };
+
+        if let Some(error) = result.error {
+            eprintln!("{}. {}: {}", id, bookmark.name, error);
+            continue;
+        }
+        let Some(new_url) = result.final_url.map(Url::from) else {
+            continue;
+        };
+        if new_url == bookmark.url || result.redirects.is_empty() {
+            continue;
+        }
+
+        moved += 1;
+        println!("{}. {}: {} -> {}", id, bookmark.name, bookmark.url, new_url);
+
+        if args.flag("rewrite") {
+            bookmark.rewrite_url(new_url);
+            bookmark.final_url = None;
+        }
+    }
+
+    if args.flag("rewrite") {
+        println!("Rewrote {} bookmarks", moved);
+    } else {
+        println!(
+            "{} bookmarks redirect elsewhere; use --rewrite to update them",
+            moved
+        );
+    }
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "resolve",
+    description: "Follow redirects and optionally rewrite bookmarks to their final URLs",
+    usage: "resolve [selection...] [--rewrite] [--jobs <n>]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
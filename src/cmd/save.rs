@@ -1,16 +1,64 @@
+use std::path::Path;
+
+use super::args::Args;
+use super::error::InvalidArguments;
 use super::{Command, Dispatch};
-use anyhow::Result;
+use crate::config;
+use anyhow::{Context, Result};
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [path] => dispatch.store().save(path),
-        _ => anyhow::bail!("Invalid arguments"),
+    let args = Args::parse(args, &["force"], &[])?;
+    let store = dispatch.store_mut();
+    let path = match args.positional() {
+        [] => store
+            .path
+            .clone()
+            .context("The store has no file to save to")?,
+        [path] => config::expand_home(Path::new(path)),
+        _ => anyhow::bail!(InvalidArguments),
+    };
+
+    // Changes made to the file elsewhere are merged in, unless forced
+    if args.flag("force") {
+        store.save_force(&path)?;
+    } else if let Some(merged) = store.save(&path)? {
+        println!("{}", merged);
+        // Merging renumbers bookmarks, so the last results no longer point at them
+        dispatch.set_last(Vec::new());
     }
+
+    Ok(())
 }
 
 inventory::submit!(Command {
     name: "save",
     description: "Save bookmarks to a file",
-    usage: "save <path>",
+    usage: "save [path] [--force]",
+    destructive: false,
+    accepts_input: false,
     run,
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_merge_forgets_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.inno");
+        fs::write(&path, "a: https://a.example []\nb: https://b.example []\n").unwrap();
+
+        let mut dispatch = Dispatch::default();
+        dispatch.store_mut().load(&path).unwrap();
+        dispatch.run_line("find b.example").unwrap();
+        assert_eq!(dispatch.selection(&["last"]).unwrap(), vec![1]);
+
+        // Removing `a` elsewhere moves `b` to ID 0, so `last` would pick the wrong bookmark
+        fs::write(&path, "b: https://b.example []\n").unwrap();
+        dispatch.run_line("save").unwrap();
+        assert_eq!(dispatch.store().get(0).unwrap().name, "b");
+        assert!(dispatch.selection(&["last"]).unwrap().is_empty());
+    }
+}
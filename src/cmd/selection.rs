@@ -0,0 +1,168 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use thiserror::Error;
+
+use super::Dispatch;
+use crate::store::{
+    Store,
+    query::{Query, QueryError},
+};
+
+#[derive(Error, Debug)]
+pub enum SelectionError {
+    #[error("Invalid selection: '{0}' (expected IDs like 3, 3-9 or 1,4,7, 'last', or q:<query>)")]
+    Invalid(String),
+    #[error("Bookmark {0} not found")]
+    NotFound(usize),
+    #[error("'{0}' selects {1} bookmarks; expected one")]
+    NotOne(String, usize),
+    #[error(transparent)]
+    Query(#[from] QueryError),
+}
+
+/// A way of picking bookmarks, accepted wherever a bookmark ID is.
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    /// IDs and inclusive ranges of IDs, e.g. `3`, `3-9` or `1,4-6,9`.
+    Ids(Vec<RangeInclusive<usize>>),
+    /// The results of the last `find`.
+    Last,
+    /// The results of a query, e.g. `q:tag:old` or `'q:rust sort:name'`.
+    Query(Query),
+}
+
+impl Selection {
+    /// Returns the IDs of the selected bookmarks, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ID or range refers to a bookmark that does not exist.
+    pub fn resolve(&self, store: &Store, last: &[usize]) -> Result<Vec<usize>, SelectionError> {
+        match self {
+            Selection::Ids(ranges) => {
+                let mut ids = Vec::new();
+                for range in ranges {
+                    if *range.end() >= store.bookmark_count() {
+                        let missing = (*range.start()).max(store.bookmark_count());
+                        return Err(SelectionError::NotFound(missing));
+                    }
+                    ids.extend(range.clone());
+                }
+                Ok(ids)
+            }
+            // Removing bookmarks forgets the results, but IDs past the end are left out regardless
+            Selection::Last => Ok(last
+                .iter()
+                .copied()
+                .filter(|&id| id < store.bookmark_count())
+                .collect()),
+            Selection::Query(query) => Ok(store.query(query).iter().map(|b| b.id).collect()),
+        }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = SelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "last" {
+            return Ok(Selection::Last);
+        }
+        if let Some(query) = s.strip_prefix("q:") {
+            return Ok(Selection::Query(query.parse()?));
+        }
+
+        let invalid = || SelectionError::Invalid(s.to_string());
+        let id = |id: &str| id.trim().parse::<usize>().map_err(|_| invalid());
+        s.split(',')
+            .map(|item| match item.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (id(start)?, id(end)?);
+                    if start > end {
+                        return Err(invalid());
+                    }
+                    Ok(start..=end)
+                }
+                None => id(item).map(|id| id..=id),
+            })
+            .collect::<Result<_, _>>()
+            .map(Selection::Ids)
+    }
+}
+
+impl Dispatch {
+    /// Resolves selection arguments to bookmark IDs, in order and without repeats.
+    pub fn selection<S: AsRef<str>>(&self, specs: &[S]) -> Result<Vec<usize>, SelectionError> {
+        let mut ids = Vec::new();
+        for spec in specs {
+            let selection: Selection = spec.as_ref().parse()?;
+            for id in selection.resolve(self.store(), self.last())? {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Resolves a selection argument that must pick exactly one bookmark.
+    pub fn selected_one(&self, spec: &str) -> Result<usize, SelectionError> {
+        match self.selection(&[spec])?[..] {
+            [id] => Ok(id),
+            ref ids => Err(SelectionError::NotOne(spec.to_string(), ids.len())),
+        }
+    }
+}
+
+/// Returns `true` if every argument is a selection rather than search terms.
+pub fn is_selection<S: AsRef<str>>(args: &[S]) -> bool {
+    !args.is_empty()
+        && args
+            .iter()
+            .all(|arg| arg.as_ref().parse::<Selection>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection() {
+        let mut dispatch = Dispatch::default();
+        for (name, tags) in [("a", "old"), ("b", "new"), ("c", "old"), ("d", "new")] {
+            dispatch.store_mut().create(
+                name.to_string(),
+                format!("https://example.com/{}", name).into(),
+                vec![tags.to_string()],
+            );
+        }
+
+        assert_eq!(dispatch.selection(&["2"]).unwrap(), vec![2]);
+        assert_eq!(dispatch.selection(&["1-3"]).unwrap(), vec![1, 2, 3]);
+        assert_eq!(dispatch.selection(&["3,0-1", "1"]).unwrap(), vec![3, 0, 1]);
+        assert_eq!(dispatch.selection(&["q:tag:old"]).unwrap(), vec![0, 2]);
+        assert_eq!(
+            dispatch.selection(&["q:tag:new sort:-name"]).unwrap(),
+            vec![3, 1]
+        );
+
+        dispatch.run_line("find tag:new").unwrap();
+        assert_eq!(dispatch.selection(&["last"]).unwrap(), vec![1, 3]);
+
+        assert!(matches!(
+            dispatch.selection(&["2-9"]),
+            Err(SelectionError::NotFound(4))
+        ));
+        for invalid in ["3-1", "1,,2", "x", "-1"] {
+            assert!(matches!(
+                dispatch.selection(&[invalid]),
+                Err(SelectionError::Invalid(_))
+            ));
+        }
+
+        assert_eq!(dispatch.selected_one("1").unwrap(), 1);
+        assert!(dispatch.selected_one("q:tag:old").is_err());
+        assert!(is_selection(&["1", "last"]));
+        assert!(!is_selection(&["1", "rust"]));
+    }
+}
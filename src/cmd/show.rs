@@ -1,17 +1,48 @@
+use super::args::Args;
+use super::listing::{self, Listing};
+use super::selection;
 use super::{Command, Dispatch};
+use crate::store::query::Query;
 use anyhow::{Context, Result};
 
 pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
-    match args {
-        [id] => {
-            let id = id.parse().context("Invalid ID")?;
-            let bookmark = dispatch.store().get(id).context("Bookmark not found")?;
-            println!("{}", bookmark);
+    let switches = [&["archived"], listing::SWITCHES].concat();
+    let args = Args::parse(args, &switches, listing::OPTIONS)?;
+    let listing = Listing::from_args(&args)?;
+
+    if let Some(input) = dispatch.take_input() {
+        listing.show(dispatch, &input)?;
+        return Ok(());
+    }
+
+    match args.positional() {
+        [selection] if args.flag("archived") => {
+            let id = dispatch.selected_one(selection)?;
+            let store = dispatch.store();
+            let bookmark = store.get(id).context("Bookmark not found")?;
+            let hash = bookmark
+                .archive
+                .as_deref()
+                .context("Bookmark has not been archived")?;
+            let path = store
+                .archive_path(hash)
+                .context("The store has no file to archive next to")?;
+            println!("{}", path.display());
+        }
+        selections if selection::is_selection(selections) => {
+            let ids = dispatch.selection(selections)?;
+            listing.show(dispatch, &ids)?;
         }
-        _ => {
-            for bookmark in dispatch.store().iter() {
-                println!("{}", bookmark);
-            }
+        terms => {
+            // With no terms, the empty query matches every bookmark in the current collection
+            let query = Query::parse(&dispatch.scoped(terms)?)?;
+            let ids: Vec<usize> = dispatch
+                .store()
+                .query(&query)
+                .iter()
+                .map(|b| b.id)
+                .collect();
+            listing.show(dispatch, &ids)?;
         }
     }
 
@@ -21,6 +52,8 @@ pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
 inventory::submit!(Command {
     name: "show",
     description: "Show bookmarks",
-    usage: "show [id]",
+    usage: "show [selection...|terms...] [in:<collection>] [--archived] [--sort name|url|added|visits|tag|...] [--reverse] [--limit n] [--offset n]",
+    destructive: false,
+    accepts_input: true,
     run,
 });
@@ -1,32 +1,31 @@
 use anyhow::Result;
+use serde_json::{Value, json};
 
+use super::render;
 use super::{Command, Dispatch};
 
 use itertools::Itertools;
 
 pub fn run(dispatch: &mut Dispatch, _: &[String]) -> Result<()> {
-    println!("inno v{}", env!("CARGO_PKG_VERSION"));
-
-    println!(
-        "entries: {}; bookmarks: {}",
-        dispatch.store().entry_count(),
-        dispatch.store().bookmark_count()
+    let store = dispatch.store();
+    let tags: Vec<Value> = store
+        .tag_index
+        .iter()
+        .sorted_by_key(|(tag, ids)| (-(ids.len() as i64), *tag))
+        .map(|(tag, _)| json!(tag))
+        .collect();
+
+    let fields = [
+        ("version", json!(env!("CARGO_PKG_VERSION"))),
+        ("entries", json!(store.entry_count())),
+        ("bookmarks", json!(store.bookmark_count())),
+        ("tags", Value::Array(tags)),
+    ];
+    print!(
+        "{}",
+        render::fields(dispatch.format(), dispatch.style(), &fields)
     );
 
-    let tags = &dispatch.store().tag_index;
-
-    if tags.is_empty() {
-        println!("no tags");
-    } else {
-        println!(
-            "tags: {}",
-            tags.iter()
-                .sorted_by_key(|(_, ids)| -(ids.len() as i64))
-                .map(|(tag, _)| tag)
-                .join(", ")
-        );
-    }
-
     Ok(())
 }
 
@@ -34,5 +33,7 @@ inventory::submit!(Command {
     name: "status",
     description: "Show status",
     usage: "status",
+    destructive: false,
+    accepts_input: false,
     run,
 });
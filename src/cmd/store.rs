@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use super::error::{InvalidArgument, InvalidArguments};
+use super::{Command, Dispatch};
+use crate::config;
+use crate::store::Store;
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    match args {
+        [] => list(dispatch),
+        [action, name, path] if action == "open" => {
+            let path = config::expand_home(Path::new(path));
+            let mut store = Store::new();
+            if path.exists() {
+                store.load(&path)?;
+            } else {
+                // Like the configured store, a new file is created on the first save
+                store.path = Some(path);
+            }
+            let count = store.bookmark_count();
+            dispatch.open_store(name, store)?;
+            println!("Opened store {} with {} bookmarks", name, count);
+        }
+        [action, name] if action == "close" => {
+            // Saving first, so that a conflict leaves the store open
+            let store = dispatch.other_store_mut(name)?;
+            if store.path.is_some()
+                && let Some(merged) = store.save_to_path()?
+            {
+                println!("{}", merged);
+            }
+            dispatch.close_store(name)?;
+            println!("Closed store {}", name);
+        }
+        [action, ..] if action == "open" || action == "close" => anyhow::bail!(InvalidArguments),
+        [action, ..] => anyhow::bail!(InvalidArgument(action.clone())),
+    }
+
+    Ok(())
+}
+
+/// Lists the open stores, marking the active one.
+fn list(dispatch: &Dispatch) {
+    for (name, store) in dispatch.stores() {
+        let active = if name == dispatch.store_name() {
+            "*"
+        } else {
+            " "
+        };
+        let path = store
+            .path
+            .as_ref()
+            .map_or("(not saved)".to_string(), |path| path.display().to_string());
+        println!(
+            "{} {} {} ({} bookmarks)",
+            active,
+            name,
+            path,
+            store.bookmark_count()
+        );
+    }
+}
+
+inventory::submit!(Command {
+    name: "store",
+    description: "List, open or close named stores",
+    usage: "store [open <name> <path> | close <name>]",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("work.inno");
+        let mut dispatch = Dispatch::default();
+        for line in [
+            "add --offline Rust https://www.rust-lang.org rust",
+            "add --offline Go https://go.dev go",
+            &format!("store open work {}", work.display()),
+            "add --offline Docs https://docs.rs rust",
+        ] {
+            dispatch.run_line(line).unwrap();
+        }
+        assert!(dispatch.run_line("store open work elsewhere").is_err());
+
+        // Moving takes bookmarks out of the active store; copying leaves them
+        dispatch
+            .run_line("move 1 work --yes; copy q:tag:rust --to work")
+            .unwrap();
+        let names = |store: &Store| store.iter().map(|b| b.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(dispatch.store()), vec!["Rust", "Docs"]);
+
+        dispatch.run_line("use work").unwrap();
+        assert_eq!(dispatch.store_name(), "work");
+        assert_eq!(names(dispatch.store()), vec!["Go", "Rust", "Docs"]);
+        assert!(dispatch.run_line("move 0 work --yes").is_err());
+        assert!(dispatch.run_line("use personal").is_err());
+
+        dispatch.run_line("use default; store close work").unwrap();
+        assert_eq!(dispatch.stores().len(), 1);
+        let mut saved = Store::new();
+        saved.load(&work).unwrap();
+        assert_eq!(names(&saved), vec!["Go", "Rust", "Docs"]);
+    }
+
+    #[test]
+    fn test_move_carries_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut dispatch = Dispatch::default();
+        dispatch.store_mut().path = Some(dir.path().join("home.inno"));
+        for line in [
+            "add --offline Rust https://www.rust-lang.org",
+            "add --offline Go https://go.dev",
+            &format!("store open work {}", dir.path().join("work.inno").display()),
+        ] {
+            dispatch.run_line(line).unwrap();
+        }
+
+        // Only the first bookmark's archived page exists
+        let archive = dispatch.store().archive_dir().unwrap();
+        std::fs::create_dir_all(&archive).unwrap();
+        std::fs::write(archive.join("aaaa.html"), "page").unwrap();
+        for (id, hash) in [(0, "aaaa"), (1, "bbbb")] {
+            let mut bookmark = dispatch.store_mut().get_mut(id).unwrap();
+            bookmark.untracked().archive = Some(hash.to_string());
+        }
+
+        dispatch.run_line("move 0-1 work --yes").unwrap();
+        dispatch.run_line("use work").unwrap();
+        let store = dispatch.store();
+        assert_eq!(store.get(0).unwrap().archive.as_deref(), Some("aaaa"));
+        assert!(store.archive_path("aaaa").unwrap().exists());
+        assert_eq!(store.get(1).unwrap().archive, None);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{fmt::Write, io::IsTerminal};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Colours that tags are drawn in, as ANSI foreground codes.
+const TAG_COLORS: &[u8] = &[31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+/// The narrowest a column is truncated to.
+const MIN_WIDTH: usize = 8;
+
+/// How output is decorated for the terminal it is written to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    /// Whether to use ANSI colours.
+    pub color: bool,
+    /// The width to fit tables into, if known.
+    pub width: Option<usize>,
+}
+
+impl Style {
+    /// Detects the style for standard output: colour and a width only for a terminal, and no
+    /// colour when `$NO_COLOR` is set.
+    pub fn detect() -> Self {
+        let terminal = std::io::stdout().is_terminal();
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self {
+            color: terminal && !no_color,
+            width: terminal
+                .then(terminal_size::terminal_size)
+                .flatten()
+                .map(|(width, _)| usize::from(width.0)),
+        }
+    }
+
+    /// Returns a tag drawn in its colour, which depends only on the tag.
+    pub fn tag(&self, tag: &str) -> Cell {
+        // FNV-1a, so that colours do not change between builds
+        let hash = tag.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        let color = TAG_COLORS[(hash % TAG_COLORS.len() as u64) as usize];
+        self.paint(tag, color)
+    }
+
+    /// Returns a list of tags, each in its colour.
+    pub fn tags<S: AsRef<str>>(&self, tags: &[S]) -> Cell {
+        let mut cell = Cell::default();
+        for (i, tag) in tags.iter().enumerate() {
+            if i > 0 {
+                cell.push(&Cell::plain(", "));
+            }
+            cell.push(&self.tag(tag.as_ref()));
+        }
+        cell
+    }
+
+    /// Returns text in bold.
+    pub fn bold(&self, text: &str) -> Cell {
+        self.paint(text, 1)
+    }
+
+    fn paint(&self, text: &str, code: u8) -> Cell {
+        let mut cell = Cell::plain(text);
+        if self.color {
+            cell.styled = format!("\x1b[{}m{}\x1b[0m", code, cell.text);
+        }
+        cell
+    }
+}
+
+/// Text in a table, with and without escape sequences.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cell {
+    text: String,
+    styled: String,
+}
+
+impl Cell {
+    /// Creates a cell without styling.
+    ///
+    /// Control characters such as tabs and newlines would break the row, so they become spaces.
+    pub fn plain(text: &str) -> Self {
+        let text: String = text
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        Self {
+            styled: text.clone(),
+            text,
+        }
+    }
+
+    fn push(&mut self, other: &Cell) {
+        self.text.push_str(&other.text);
+        self.styled.push_str(&other.styled);
+    }
+
+    fn width(&self) -> usize {
+        self.text.width()
+    }
+
+    /// Shortens the cell to a display width, ending it with '…'; styling is dropped.
+    fn truncate(&mut self, width: usize) {
+        if self.width() <= width {
+            return;
+        }
+        let mut text = String::new();
+        let mut used = 0;
+        for c in self.text.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w + 1 > width {
+                break;
+            }
+            used += w;
+            text.push(c);
+        }
+        text.push('…');
+        *self = Cell::plain(&text);
+    }
+}
+
+/// A table with aligned columns.
+pub struct Table {
+    header: Vec<Cell>,
+    rows: Vec<Vec<Cell>>,
+    /// Columns that may be truncated to fit the width, in the order they are shrunk.
+    flexible: Vec<usize>,
+}
+
+impl Table {
+    /// Creates an empty table with a header row.
+    pub fn new(style: &Style, header: &[&str]) -> Self {
+        Self {
+            header: header.iter().map(|title| style.bold(title)).collect(),
+            rows: Vec::new(),
+            flexible: Vec::new(),
+        }
+    }
+
+    /// Marks columns that may be truncated, the first being shrunk first.
+    pub fn flexible(mut self, columns: &[usize]) -> Self {
+        self.flexible = columns.to_vec();
+        self
+    }
+
+    /// Adds a row.
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    /// Writes the table, truncating flexible columns so that lines fit within `width`.
+    pub fn render(mut self, width: Option<usize>) -> String {
+        const GAP: usize = 2;
+
+        let mut widths: Vec<usize> = self.header.iter().map(Cell::width).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+
+        if let Some(limit) = width {
+            for &column in &self.flexible {
+                let total = widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1);
+                let excess = total.saturating_sub(limit);
+                if excess == 0 {
+                    break;
+                }
+                widths[column] = widths[column].saturating_sub(excess).max(MIN_WIDTH);
+            }
+            for row in &mut self.rows {
+                for (cell, &width) in row.iter_mut().zip(&widths) {
+                    cell.truncate(width);
+                }
+            }
+        }
+
+        let mut out = String::new();
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            let mut line = String::new();
+            for (i, (cell, width)) in row.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    line.push_str(&" ".repeat(GAP));
+                }
+                line.push_str(&cell.styled);
+                if i + 1 < row.len() {
+                    line.push_str(&" ".repeat(width.saturating_sub(cell.width())));
+                }
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let style = Style::default();
+        let mut table = Table::new(&style, &["ID", "NAME", "URL"]).flexible(&[2, 1]);
+        table.push(vec![
+            Cell::plain("0"),
+            Cell::plain("Bücher"),
+            Cell::plain("https://example.com/a/very/long/path"),
+        ]);
+
+        assert_eq!(
+            table.render(Some(30)),
+            "ID  NAME    URL\n\
+             0   Bücher  https://example.c…\n"
+        );
+    }
+
+    #[test]
+    fn test_tag_colors() {
+        let style = Style {
+            color: true,
+            width: None,
+        };
+        assert_eq!(style.tag("rust"), style.tag("rust"));
+        assert!(style.tag("rust").styled.starts_with("\x1b["));
+        assert_eq!(Style::default().tags(&["a", "b"]), Cell::plain("a, b"));
+    }
+}
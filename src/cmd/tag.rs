@@ -0,0 +1,116 @@
+use itertools::Itertools;
+
+use super::error::{InvalidArgument, InvalidArguments, MissingArgument};
+use super::{Command, Dispatch};
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let (action, rest) = match args {
+        [] => {
+            let tags = &dispatch.store().tag_index;
+            for (tag, ids) in tags.iter().sorted_by_key(|(tag, _)| tag.as_str()) {
+                println!("{} ({})", tag, ids.len());
+            }
+            return Ok(());
+        }
+        [action, rest @ ..] if action == "add" || action == "remove" => (action, rest),
+        [action, ..] => anyhow::bail!(InvalidArgument(action.clone())),
+    };
+
+    // Without piped bookmarks, the first argument is the bookmark ID
+    let (ids, tags) = match dispatch.take_input() {
+        Some(ids) => (ids, rest),
+        None => match rest {
+            [selection, tags @ ..] => (dispatch.selection(&[selection])?, tags),
+            [] => anyhow::bail!(InvalidArguments),
+        },
+    };
+    if tags.is_empty() {
+        anyhow::bail!(MissingArgument("tags".to_string()));
+    }
+
+    let store = dispatch.store_mut();
+    let mut changed = 0;
+    for &id in &ids {
+        let Some(mut new_tags) = store.get(id).map(|b| b.tags.clone()) else {
+            anyhow::bail!("Bookmark {} not found", id);
+        };
+        let old_len = new_tags.len();
+        if action == "add" {
+            new_tags.extend(
+                tags.iter()
+                    .filter(|tag| !new_tags.contains(tag))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            );
+        } else {
+            new_tags.retain(|tag| !tags.contains(tag));
+        }
+        if new_tags.len() != old_len {
+            store.set_tags(id, new_tags);
+            changed += 1;
+        }
+    }
+
+    if !dispatch.is_piped() {
+        println!("Updated tags on {} of {} bookmarks", changed, ids.len());
+    }
+    dispatch.select(ids);
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "tag",
+    description: "List tags, or add or remove tags on bookmarks",
+    usage: "tag [add|remove <selection> <tags...>] (or piped bookmarks: ... | tag add <tags...>)",
+    destructive: false,
+    accepts_input: true,
+    run,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let mut dispatch = Dispatch::default();
+        for line in [
+            "add --offline Rust https://www.rust-lang.org rust",
+            "add --offline Cargo https://doc.rust-lang.org/cargo rust tools",
+            "add --offline Go https://go.dev go",
+        ] {
+            dispatch.run_line(line).unwrap();
+        }
+
+        dispatch
+            .run_line("find tag:rust | tag add reading | tag remove tools")
+            .unwrap();
+        let tags: Vec<_> = dispatch.store().iter().map(|b| b.tags.clone()).collect();
+        assert_eq!(
+            tags,
+            vec![vec!["rust", "reading"], vec!["rust", "reading"], vec!["go"]]
+        );
+
+        // `&&` stops at the first failure, `;` does not
+        assert!(
+            dispatch
+                .run_line("tag add 9 x && tag add 2 skipped")
+                .is_err()
+        );
+        dispatch.run_line("tag add 9 x; tag add 2 ran").unwrap();
+        assert_eq!(dispatch.store().get(2).unwrap().tags, vec!["go", "ran"]);
+
+        assert!(dispatch.run_line("find go | status").is_err());
+        assert!(dispatch.run_line("status | show").is_err());
+
+        // A command that does not read piped bookmarks fails before doing anything
+        assert!(
+            dispatch
+                .run_line("find go | add --offline Zig https://ziglang.org")
+                .is_err()
+        );
+        assert_eq!(dispatch.store().bookmark_count(), 3);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::store::refs::BookmarkRef;
+
+/// Named templates that can be used in place of a template string.
+const PRESETS: &[(&str, &str)] = &[
+    ("url", "{url}"),
+    ("markdown", "[{name}]({url})"),
+    ("plain", "{name} {url}"),
+];
+
+/// Returns the template for a preset name, or the argument itself if it is not a preset.
+pub fn resolve(template: &str) -> &str {
+    PRESETS
+        .iter()
+        .find(|(name, _)| *name == template)
+        .map_or(template, |(_, preset)| preset)
+}
+
+/// Fills in a template with a bookmark's fields.
+///
+/// Supported placeholders are `{id}`, `{name}`, `{url}`, `{tags}` and `{host}`; anything else in
+/// braces is left as it is.
+pub fn expand(template: &str, bookmark: &BookmarkRef) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let placeholder = &rest[start..=start + len];
+        match &placeholder[1..len] {
+            "id" => out.push_str(&bookmark.id.to_string()),
+            "name" => out.push_str(&bookmark.name),
+            "url" => out.push_str(&bookmark.url.to_string()),
+            "tags" => out.push_str(&bookmark.tags.join(", ")),
+            "host" => out.push_str(&bookmark.url.host().unwrap_or_default()),
+            _ => out.push_str(placeholder),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::bookmark::Bookmark;
+
+    #[test]
+    fn test_expand() {
+        let bookmark = Bookmark::new(
+            "Rust".to_string(),
+            "https://www.rust-lang.org/learn".into(),
+            vec!["rust".to_string(), "lang".to_string()],
+        );
+        let bookmark = BookmarkRef::new(3, &bookmark);
+
+        assert_eq!(
+            expand(resolve("markdown"), &bookmark),
+            "[Rust](https://www.rust-lang.org/learn)"
+        );
+        assert_eq!(
+            expand("{id}: {host} ({tags}) {other} {", &bookmark),
+            "3: www.rust-lang.org (rust, lang) {other} {"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::error::InvalidArguments;
+use super::render;
+use super::{Command, Dispatch};
+use crate::store::bookmark::Bookmark;
+use anyhow::{Context, Result};
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    // The store comes last, after the bookmarks if they are not piped in
+    let (name, selections) = args.split_last().ok_or(InvalidArguments)?;
+    let mut ids = match dispatch.take_input() {
+        Some(input) if selections.is_empty() => input,
+        None if !selections.is_empty() => dispatch.selection(selections)?,
+        _ => anyhow::bail!(InvalidArguments),
+    };
+    if ids.is_empty() {
+        println!("No bookmarks selected");
+        return Ok(());
+    }
+    dispatch.other_store_mut(name)?;
+
+    let store = dispatch.store();
+    let bookmarks: Vec<_> = ids.iter().filter_map(|&id| store.get(id)).collect();
+    let preview = render::bookmarks(dispatch.format(), dispatch.style(), &bookmarks);
+    let question = match ids.len() {
+        1 => format!("Move this bookmark to {}?", name),
+        n => format!("Move these {} bookmarks to {}?", n, name),
+    };
+    if !dispatch.confirm(&preview, &question)? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    let count = transfer(dispatch, &ids, name)?;
+
+    // Removing shifts the IDs after each removed bookmark, so go from the highest down
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    for &id in &ids {
+        dispatch.store_mut().remove(id);
+    }
+    dispatch.set_last(Vec::new());
+    println!("Moved {} bookmarks to {}", count, name);
+
+    Ok(())
+}
+
+/// Adds copies of bookmarks from the active store to another open store, returning how many.
+///
+/// Archived pages are kept next to each store's file, so they are copied along; a bookmark
+/// whose archive cannot be copied loses its reference to it.
+pub fn transfer(dispatch: &mut Dispatch, ids: &[usize], name: &str) -> Result<usize> {
+    let store = dispatch.store();
+    let bookmarks: Vec<(Bookmark, Option<PathBuf>)> = ids
+        .iter()
+        .filter_map(|&id| store.get(id))
+        .map(|bookmark| {
+            let archive = bookmark
+                .archive
+                .as_deref()
+                .and_then(|hash| store.archive_path(hash).filter(|path| path.exists()));
+            (bookmark.bookmark.clone(), archive)
+        })
+        .collect();
+
+    let target = dispatch.other_store_mut(name)?;
+    let target_dir = target.archive_dir();
+    for (bookmark, _) in &bookmarks {
+        if let Some(existing) = target.find_url(bookmark.url.as_str()) {
+            eprintln!("Warning: {} already has {}", name, existing);
+        }
+    }
+    let count = bookmarks.len();
+    for (mut bookmark, archive) in bookmarks {
+        if bookmark.archive.is_some() {
+            let copied = match (archive, &target_dir) {
+                (Some(source), Some(dir)) => copy_archive(&source, dir),
+                (None, _) => Err(anyhow::anyhow!("the archived page is missing")),
+                (_, None) => Err(anyhow::anyhow!("{} has no file to archive next to", name)),
+            };
+            if let Err(e) = copied {
+                eprintln!(
+                    "Warning: the archived copy of {} was not carried over: {:#}",
+                    bookmark, e
+                );
+                bookmark.archive = None;
+            }
+        }
+        target.add(bookmark);
+    }
+
+    Ok(count)
+}
+
+/// Copies an archived page into another archive directory, under the same file name.
+fn copy_archive(source: &Path, dir: &Path) -> Result<()> {
+    let file_name = source
+        .file_name()
+        .context("Archived page has no file name")?;
+    let path = dir.join(file_name);
+    if !path.exists() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create archive directory {}", dir.display()))?;
+        fs::copy(source, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "move",
+    description: "Move bookmarks to another open store",
+    usage: "move <selection...> <store> (or piped bookmarks: ... | move <store>) [--yes]",
+    destructive: true,
+    accepts_input: true,
+    run,
+});
@@ -0,0 +1,26 @@
+use super::error::InvalidArguments;
+use super::{Command, Dispatch};
+use anyhow::Result;
+
+pub fn run(dispatch: &mut Dispatch, args: &[String]) -> Result<()> {
+    let [name] = args else {
+        anyhow::bail!(InvalidArguments);
+    };
+    dispatch.use_store(name)?;
+    println!(
+        "Using store {} ({} bookmarks)",
+        name,
+        dispatch.store().bookmark_count()
+    );
+
+    Ok(())
+}
+
+inventory::submit!(Command {
+    name: "use",
+    description: "Switch to another open store",
+    usage: "use <store>",
+    destructive: false,
+    accepts_input: false,
+    run,
+});
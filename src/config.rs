@@ -0,0 +1,232 @@
+//! User configuration, read from `$XDG_CONFIG_HOME/inno/config.toml`.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The environment variable that overrides the location of the config file.
+pub const CONFIG_VAR: &str = "INNO_CONFIG";
+
+/// Settings that change how inno behaves.
+///
+/// Every field has a default, so a config file only needs the settings it changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The store file loaded at startup and used by `save` without a path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<PathBuf>,
+    /// The REPL prompt.
+    pub prompt: String,
+    /// Whether to greet the user at startup.
+    pub motd: bool,
+    /// Whether to use colour: `auto`, `always` or `never`.
+    pub color: String,
+    /// The default output format of listing commands.
+    pub format: String,
+    /// The command that opens URLs; `{url}` is replaced by the URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opener: Option<String>,
+    /// The editor used for notes and editing bookmarks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// Whether to save the store after every command that succeeds.
+    pub autosave: bool,
+    /// Whether to lowercase tags and replace spaces in them with '-'.
+    pub normalize_tags: bool,
+    /// What to do with URLs that do not parse: `reject`, `warn` or `prefix`.
+    pub url_policy: String,
+    /// Names that stand for other commands, e.g. `ls = "show --sort name"`.
+    ///
+    /// A body may run several commands separated by `;`; extra arguments given to the alias
+    /// are passed to the last one.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            store: None,
+            prompt: "ℂ ".to_string(),
+            motd: true,
+            color: "auto".to_string(),
+            format: "table".to_string(),
+            opener: None,
+            editor: None,
+            autosave: false,
+            normalize_tags: false,
+            url_policy: "prefix".to_string(),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Unknown config key: '{0}'")]
+    UnknownKey(String),
+    #[error("Invalid value for '{0}': {1}")]
+    InvalidValue(String, String),
+}
+
+impl Config {
+    /// Returns where the config file is: `$INNO_CONFIG`, or `inno/config.toml` in the XDG config
+    /// directory.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_VAR) {
+            return Some(PathBuf::from(path));
+        }
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(dir.join("inno").join("config.toml"))
+    }
+
+    /// Reads the config file, falling back to the defaults if there is none.
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::read(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Reads a config file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    /// Writes the config to a file, creating its directory if needed.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Returns the store path, with a leading `~` expanded to the home directory.
+    pub fn store_path(&self) -> Option<PathBuf> {
+        self.store.as_deref().map(expand_home)
+    }
+
+    /// Returns every set key with its value, as written in the config file.
+    ///
+    /// Aliases are not included; they are listed by the `alias` command.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.table()
+            .into_iter()
+            .filter(|(key, _)| self.check_key(key).is_ok())
+            .map(|(key, value)| (key, value.to_string()))
+            .collect()
+    }
+
+    /// Returns the value of a key, as written in the config file.
+    pub fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        self.check_key(key)?;
+        Ok(self.table().get(key).map(ToString::to_string))
+    }
+
+    /// Returns a copy of the config with a key set.
+    ///
+    /// The value is read as TOML if possible (e.g. `true`), and as a string otherwise.
+    pub fn with(&self, key: &str, value: &str) -> Result<Self, ConfigError> {
+        self.check_key(key)?;
+        let value = toml::from_str::<toml::Table>(&format!("value = {}", value))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+        let mut table = self.table();
+        table.insert(key.to_string(), value);
+        Self::from_table(key, table)
+    }
+
+    /// Returns a copy of the config with a key reset to its default.
+    pub fn without(&self, key: &str) -> Result<Self, ConfigError> {
+        self.check_key(key)?;
+        let mut table = self.table();
+        table.remove(key);
+        Self::from_table(key, table)
+    }
+
+    fn table(&self) -> toml::Table {
+        toml::Table::try_from(self).expect("config serializes to a table")
+    }
+
+    fn from_table(key: &str, table: toml::Table) -> Result<Self, ConfigError> {
+        table.try_into().map_err(|e: toml::de::Error| {
+            ConfigError::InvalidValue(key.to_string(), e.message().to_string())
+        })
+    }
+
+    fn check_key(&self, key: &str) -> Result<(), ConfigError> {
+        const KEYS: &[&str] = &[
+            "store",
+            "prompt",
+            "motd",
+            "color",
+            "format",
+            "opener",
+            "editor",
+            "autosave",
+            "normalize_tags",
+            "url_policy",
+        ];
+        if KEYS.contains(&key) {
+            Ok(())
+        } else {
+            Err(ConfigError::UnknownKey(key.to_string()))
+        }
+    }
+}
+
+/// Expands a leading `~` in a path to the home directory.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_file() {
+        let config: Config = toml::from_str(
+            "autosave = true\nopener = \"firefox {url}\"\n[aliases]\nls = \"show --sort name\"",
+        )
+        .unwrap();
+        assert_eq!(config.aliases["ls"], "show --sort name");
+        assert!(config.autosave);
+        assert_eq!(config.opener.as_deref(), Some("firefox {url}"));
+        assert_eq!(config.prompt, Config::default().prompt);
+
+        assert!(toml::from_str::<Config>("colour = \"never\"").is_err());
+    }
+
+    #[test]
+    fn test_get_set() {
+        let config = Config::default().with("motd", "false").unwrap();
+        assert!(!config.motd);
+        assert_eq!(config.get("motd").unwrap().as_deref(), Some("false"));
+
+        let config = config.with("store", "~/bookmarks.txt").unwrap();
+        assert_eq!(config.store, Some(PathBuf::from("~/bookmarks.txt")));
+        assert_eq!(config.without("store").unwrap().store, None);
+
+        assert!(config.with("motd", "sometimes").is_err());
+        assert!(config.get("nope").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Just enough HTML scanning to read link lists and page metadata.
+
+/// Elements that never have content or a closing tag.
+const VOID_ELEMENTS: &[&str] = &["meta", "link", "img", "base", "br", "hr", "input", "source"];
+
+/// An element: its attributes and its text content.
+pub struct Element {
+    pub attrs: Vec<(String, String)>,
+    pub text: String,
+}
+
+impl Element {
+    /// Returns the value of an attribute, matched case-insensitively.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Finds every `<a ...>text</a>` element in a document.
+pub fn anchors(html: &str) -> Vec<Element> {
+    elements(html, "a")
+}
+
+/// Finds every element with the given (lowercase) tag name in a document.
+pub fn elements(html: &str, name: &str) -> Vec<Element> {
+    let lower = html.to_ascii_lowercase();
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let void = VOID_ELEMENTS.contains(&name);
+    let mut elements = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = lower[pos..].find(&open).map(|i| pos + i) {
+        pos = start + open.len();
+        if !lower[pos..].starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/') {
+            continue;
+        }
+
+        let Some(tag_end) = tag_end(html, pos) else {
+            break;
+        };
+        let attrs = parse_attrs(&html[pos..tag_end]);
+
+        let text_start = tag_end + 1;
+        let text_end = if void {
+            text_start
+        } else {
+            lower[text_start..]
+                .find(&close)
+                .map_or(html.len(), |i| text_start + i)
+        };
+        let text = decode_entities(strip_tags(&html[text_start..text_end]).trim());
+
+        elements.push(Element { attrs, text });
+        pos = text_end;
+    }
+
+    elements
+}
+
+/// Returns the index of the `>` closing a tag, skipping over quoted attribute values.
+fn tag_end(html: &str, from: usize) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in html[from..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(from + i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `key="value" key='value' key=value key` attributes.
+pub fn parse_attrs(text: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = text.trim_end_matches('/').chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let key: String =
+            std::iter::from_fn(|| chars.next_if(|&c| !c.is_whitespace() && c != '=')).collect();
+        if key.is_empty() {
+            break;
+        }
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match chars.next_if(|&c| c == '"' || c == '\'') {
+                Some(quote) => value.extend(std::iter::from_fn(|| chars.next_if(|&c| c != quote))),
+                None => value.extend(std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace()))),
+            }
+            chars.next_if(|&c| c == '"' || c == '\'');
+        }
+
+        attrs.push((key.to_ascii_lowercase(), decode_entities(&value)));
+    }
+
+    attrs
+}
+
+/// Removes any tags from a fragment of HTML, leaving its text.
+pub fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Decodes the common named entities and numeric character references.
+pub fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchors() {
+        let html = r#"<ul><li><A HREF="https://a.example/?x=1&amp;y=2" tags='one,two'>A &lt;b&gt; <b>link</b></A></li>
+            <li><abbr>no</abbr><a href=https://b.example>B</a></ul>"#;
+
+        let anchors = anchors(html);
+        assert_eq!(anchors.len(), 2);
+        assert_eq!(anchors[0].attr("href"), Some("https://a.example/?x=1&y=2"));
+        assert_eq!(anchors[0].attr("tags"), Some("one,two"));
+        assert_eq!(anchors[0].text, "A <b> link");
+        assert_eq!(anchors[1].attr("href"), Some("https://b.example"));
+        assert_eq!(anchors[1].text, "B");
+    }
+
+    #[test]
+    fn test_void_elements() {
+        let html = r#"<head><meta charset="utf-8"><META name=keywords content="a, b"/><title>T</title></head>"#;
+
+        let metas = elements(html, "meta");
+        assert_eq!(metas.len(), 2);
+        assert_eq!(metas[1].attr("name"), Some("keywords"));
+        assert_eq!(metas[1].attr("content"), Some("a, b"));
+        assert_eq!(elements(html, "title")[0].text, "T");
+    }
+}
@@ -1,10 +1,34 @@
 use anyhow::Result;
 
 pub mod cmd;
+pub mod config;
+pub mod html;
+pub mod net;
 pub mod store;
+pub mod sys;
 
 fn main() -> Result<()> {
-    if let Err(e) = cmd::Dispatch::default().start() {
+    let config = config::Config::load().unwrap_or_else(|e| {
+        eprintln!("Error: {:#}; using the default config", e);
+        config::Config::default()
+    });
+    let mut dispatch = cmd::Dispatch::from_config(config).unwrap_or_else(|e| {
+        eprintln!("Error: {:#}; using the default config", e);
+        cmd::Dispatch::default()
+    });
+    // Without its store, inno still runs with the rest of the config, so that it can be fixed
+    if let Err(e) = dispatch.load_store() {
+        eprintln!("Error: {:#}", e);
+    }
+    // For scripts: `inno --yes < commands` runs destructive commands without asking
+    if std::env::args()
+        .skip(1)
+        .any(|arg| arg == "--yes" || arg == "-y")
+    {
+        dispatch.set_assume_yes(true);
+    }
+
+    if let Err(e) = dispatch.start() {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
@@ -0,0 +1,257 @@
+//! Downloading pages into a content-addressed archive.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use ureq::{Agent, ResponseExt};
+
+use crate::html;
+
+/// The largest page or asset that will be archived.
+const MAX_SIZE: u64 = 32 * 1024 * 1024;
+
+/// A page downloaded for archiving.
+pub struct Page {
+    /// The URL the page was served from, after redirects.
+    pub url: String,
+    pub content: Vec<u8>,
+    /// The MIME type the page was served as.
+    pub mime: String,
+}
+
+/// Downloads a page, optionally inlining its images, stylesheets and scripts as `data:` URLs.
+///
+/// A `<base>` element is added so that links that were not inlined still resolve against
+/// the original site.
+pub fn download(agent: &Agent, url: &str, inline: bool) -> Result<Page> {
+    let (final_url, content, mime) = fetch(agent, url)?;
+    if !mime.contains("html") {
+        return Ok(Page {
+            url: final_url,
+            content,
+            mime,
+        });
+    }
+
+    let mut page = String::from_utf8_lossy(&content).into_owned();
+    if inline {
+        page = inline_assets(agent, &page, &final_url);
+    }
+    page = add_base(&page, &final_url);
+
+    Ok(Page {
+        url: final_url,
+        content: page.into_bytes(),
+        mime,
+    })
+}
+
+/// Fetches a URL, returning the final URL, the body and its MIME type.
+fn fetch(agent: &Agent, url: &str) -> Result<(String, Vec<u8>, String)> {
+    let mut response = agent
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status().as_u16()
+        );
+    }
+
+    let final_url = response.get_uri().to_string();
+    let mime = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let content = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_SIZE)
+        .read_to_vec()
+        .with_context(|| format!("Failed to read {}", url))?;
+
+    Ok((final_url, content, mime))
+}
+
+/// Replaces references to images, stylesheets and scripts with `data:` URLs.
+///
+/// Assets that cannot be fetched are left pointing at the original site.
+fn inline_assets(agent: &Agent, page: &str, base: &str) -> String {
+    let Ok(base) = url::Url::parse(base) else {
+        return page.to_string();
+    };
+
+    let mut references = HashSet::new();
+    for (element, attr) in [("img", "src"), ("script", "src"), ("link", "href")] {
+        for element in html::elements(page, element) {
+            if element
+                .attr("rel")
+                .is_some_and(|rel| !rel.eq_ignore_ascii_case("stylesheet"))
+            {
+                continue;
+            }
+            if let Some(value) = element
+                .attr(attr)
+                .filter(|value| !value.starts_with("data:"))
+            {
+                references.insert((attr, value.to_string()));
+            }
+        }
+    }
+
+    let mut page = page.to_string();
+    for (attr, reference) in references {
+        let Ok(url) = base.join(&reference) else {
+            continue;
+        };
+        let Ok((_, content, mime)) = fetch(agent, url.as_str()) else {
+            continue;
+        };
+
+        let data = format!(
+            "data:{};base64,{}",
+            mime.split(';').next().unwrap_or_default().trim(),
+            base64::engine::general_purpose::STANDARD.encode(content)
+        );
+        // The page may spell the reference with or without escaped ampersands
+        for spelling in [reference.replace('&', "&amp;"), reference.clone()] {
+            for quote in ['"', '\''] {
+                page = page.replace(
+                    &format!("{}={}{}{}", attr, quote, spelling, quote),
+                    &format!("{}=\"{}\"", attr, data),
+                );
+            }
+        }
+    }
+
+    page
+}
+
+/// Adds a `<base>` element to the page's `<head>`, unless it already has one.
+fn add_base(page: &str, url: &str) -> String {
+    let lower = page.to_ascii_lowercase();
+    if lower.contains("<base") {
+        return page.to_string();
+    }
+
+    let element = format!("<base href=\"{}\">", url.replace('"', "&quot;"));
+    match lower
+        .find("<head")
+        .and_then(|i| lower[i..].find('>').map(|j| i + j + 1))
+    {
+        Some(i) => format!("{}{}{}", &page[..i], element, &page[i..]),
+        None => format!("{}{}", element, page),
+    }
+}
+
+/// Returns the SHA-256 hash of some content, as lowercase hex.
+pub fn hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Returns the file extension for content of a MIME type, e.g. `pdf` for `application/pdf`.
+pub fn extension(mime: &str) -> &'static str {
+    let essence = mime.split(';').next().unwrap_or_default().trim();
+    match essence.to_ascii_lowercase().as_str() {
+        "text/html" | "application/xhtml+xml" => "html",
+        "text/plain" => "txt",
+        "text/css" => "css",
+        "text/csv" => "csv",
+        "text/markdown" => "md",
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "application/xml" | "text/xml" => "xml",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "bin",
+    }
+}
+
+/// Writes content to an archive directory under its hash, returning the hash and path.
+///
+/// The file's extension is chosen from the content's MIME type. Content that is already
+/// archived is not written again.
+pub fn write(dir: &Path, content: &[u8], mime: &str) -> Result<(String, PathBuf)> {
+    let hash = hash(content);
+    let path = dir.join(format!("{}.{}", hash, extension(mime)));
+
+    if !path.exists() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create archive directory {}", dir.display()))?;
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok((hash, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::{self, test_server::TestServer};
+
+    const PAGE: &str = r#"<html><head><title>T</title><link rel="stylesheet" href="style.css"></head>
+        <body><img src="/logo.png?v=1&amp;x=2"><a href="/other">other</a></body></html>"#;
+
+    fn server() -> TestServer {
+        TestServer::start(vec![
+            ("/page", TestServer::page(200, PAGE)),
+            (
+                "/style.css",
+                TestServer::page(200, "body{}").header("Content-Type", "text/css"),
+            ),
+            (
+                "/logo.png?v=1&x=2",
+                TestServer::page(200, "PNG").header("Content-Type", "image/png"),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_download_inline() {
+        let server = server();
+        let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+
+        let page = download(&agent, &server.url("/page"), true).unwrap();
+        let content = String::from_utf8(page.content).unwrap();
+
+        assert!(content.contains(&format!("<head><base href=\"{}\">", server.url("/page"))));
+        assert!(content.contains(r#"href="data:text/css;base64,Ym9keXt9""#));
+        assert!(content.contains(r#"src="data:image/png;base64,UE5H""#));
+        assert!(content.contains(r#"href="/other""#));
+    }
+
+    #[test]
+    fn test_write_is_content_addressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        let (hash, path) = write(dir, b"page", "text/html; charset=utf-8").unwrap();
+        assert_eq!(
+            hash,
+            "3660315a9af3df255d8f19ab077e4797822b41488a0e2a04bc6af71213c23274"
+        );
+        assert_eq!(path.extension().unwrap(), "html");
+        assert_eq!(fs::read(&path).unwrap(), b"page");
+        assert_eq!(write(dir, b"page", "text/html").unwrap().1, path);
+        assert_ne!(write(dir, b"other", "text/html").unwrap().0, hash);
+
+        let (_, path) = write(dir, b"%PDF", "application/pdf").unwrap();
+        assert_eq!(path.extension().unwrap(), "pdf");
+    }
+}
@@ -0,0 +1,138 @@
+//! Checking whether bookmarked URLs still resolve.
+
+use std::{
+    sync::{Mutex, mpsc},
+    thread,
+};
+
+use ureq::{Agent, ResponseExt};
+
+use crate::store::bookmark::LinkStatus;
+
+/// The result of checking a single URL.
+pub struct Check {
+    pub status: LinkStatus,
+    /// The URL the request ended up at after following redirects.
+    pub final_url: Option<String>,
+    /// The URLs that redirected to the final URL, in order.
+    pub redirects: Vec<String>,
+    /// Why the request failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Checks a URL with a `HEAD` request, falling back to `GET` for servers that refuse `HEAD`.
+pub fn check(agent: &Agent, url: &str) -> Check {
+    let head = agent.head(url).call();
+    let response = match head {
+        Ok(response) if response.status().as_u16() < 400 => Ok(response),
+        _ => agent.get(url).call(),
+    };
+
+    match response {
+        Ok(response) => {
+            let mut redirects: Vec<String> = response
+                .get_redirect_history()
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            // The history ends with the final URL itself
+            redirects.pop();
+
+            Check {
+                status: LinkStatus::Http(response.status().as_u16()),
+                final_url: Some(response.get_uri().to_string()),
+                redirects,
+                error: None,
+            }
+        }
+        Err(e) => Check {
+            status: LinkStatus::Failed,
+            final_url: None,
+            redirects: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// The default number of requests in flight at once.
+pub const DEFAULT_JOBS: usize = 8;
+
+/// Checks many URLs, running at most `jobs` requests at a time.
+///
+/// Results are returned in the order they complete, paired with the key given for each URL.
+pub fn check_all<K: Send>(agent: &Agent, urls: Vec<(K, String)>, jobs: usize) -> Vec<(K, Check)> {
+    let queue = Mutex::new(urls.into_iter());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            let sender = sender.clone();
+            let queue = &queue;
+            scope.spawn(move || {
+                loop {
+                    let next = queue.lock().expect("queue lock poisoned").next();
+                    let Some((key, url)) = next else {
+                        break;
+                    };
+                    if sender.send((key, check(agent, &url))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    receiver.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::{self, test_server::TestServer};
+
+    #[test]
+    fn test_check_all() {
+        let server = TestServer::start(vec![
+            ("/ok", TestServer::page(200, "hello")),
+            ("/old", TestServer::redirect(301, "/ok")),
+            ("/gone", TestServer::page(404, "not found")),
+            ("/no-head", TestServer::page(200, "hi").without_head()),
+        ]);
+        let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+
+        let urls = ["/ok", "/old", "/gone", "/no-head"]
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (i, server.url(path)))
+            .collect();
+        let mut results = check_all(&agent, urls, 2);
+        results.sort_by_key(|(i, _)| *i);
+
+        let statuses: Vec<_> = results.iter().map(|(_, check)| check.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                LinkStatus::Http(200),
+                LinkStatus::Http(200),
+                LinkStatus::Http(404),
+                LinkStatus::Http(200)
+            ]
+        );
+        assert_eq!(
+            results[1].1.final_url.as_deref(),
+            Some(server.url("/ok").as_str())
+        );
+        assert_eq!(results[1].1.redirects, vec![server.url("/old")]);
+        assert!(results[0].1.redirects.is_empty());
+    }
+
+    #[test]
+    fn test_check_unreachable() {
+        let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+        let check = check(&agent, &TestServer::unreachable_url());
+        assert_eq!(check.status, LinkStatus::Failed);
+        assert!(check.error.is_some());
+    }
+}
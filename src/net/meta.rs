@@ -0,0 +1,152 @@
+//! Fetching a page's title, description and keywords.
+
+use anyhow::{Context, Result};
+use ureq::Agent;
+
+use crate::{html, store::url::Url};
+
+/// The most of a page that is read when looking for its metadata.
+const MAX_PAGE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Metadata found in a page's `<head>`.
+#[derive(Debug, Default, PartialEq)]
+pub struct PageMeta {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+/// Fetches a page and reads its metadata.
+pub fn fetch(agent: &Agent, url: &str) -> Result<PageMeta> {
+    let mut response = agent.get(url).call().context("Failed to fetch page")?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Failed to fetch page: HTTP {}", status.as_u16());
+    }
+
+    let body = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_PAGE_SIZE)
+        .read_to_vec()
+        .context("Failed to read page")?;
+
+    Ok(parse(&String::from_utf8_lossy(&body)))
+}
+
+/// Reads the metadata of an HTML page, preferring OpenGraph properties where present.
+pub fn parse(page: &str) -> PageMeta {
+    let metas = html::elements(page, "meta");
+    let meta = |key: &str| {
+        metas
+            .iter()
+            .find(|meta| {
+                meta.attr("property")
+                    .or_else(|| meta.attr("name"))
+                    .is_some_and(|name| name.eq_ignore_ascii_case(key))
+            })
+            .and_then(|meta| meta.attr("content"))
+            .map(collapse_whitespace)
+            .filter(|content| !content.is_empty())
+    };
+
+    let title = meta("og:title").or_else(|| {
+        html::elements(page, "title")
+            .first()
+            .map(|title| collapse_whitespace(&title.text))
+            .filter(|title| !title.is_empty())
+    });
+    let description = meta("og:description").or_else(|| meta("description"));
+    let keywords = meta("keywords")
+        .map(|keywords| {
+            keywords
+                .split(',')
+                .map(|keyword| keyword.trim().to_lowercase().replace([' ', '[', ']'], "-"))
+                .filter(|keyword| !keyword.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PageMeta {
+        title,
+        description,
+        keywords,
+    }
+}
+
+/// Derives a readable name from a URL, for when the page itself cannot be fetched.
+///
+/// Uses the host without `www.`, followed by the last path segment if there is one
+/// (e.g. `example.com/getting-started` becomes "example.com - getting started").
+pub fn name_from_url(url: &Url) -> String {
+    let Some(parsed) = url.parsed() else {
+        return url.as_str().to_string();
+    };
+
+    let host = url.host().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let segment = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .map(|segment| {
+            let segment = segment.rsplit_once('.').map_or(segment, |(stem, _)| stem);
+            segment.replace(['-', '_', '+'], " ")
+        });
+
+    match (host, segment) {
+        ("", _) => url.as_str().to_string(),
+        (host, Some(segment)) if !segment.is_empty() => format!("{} - {}", host, segment),
+        (host, _) => host.to_string(),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::{self, test_server::TestServer};
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+        <html><head>
+            <title>
+                The Rust
+                Programming Language
+            </title>
+            <meta name="description" content="A book about Rust">
+            <meta name="keywords" content="Rust, Programming Languages, ">
+        </head><body></body></html>"#;
+
+    #[test]
+    fn test_parse() {
+        let meta = parse(PAGE);
+        assert_eq!(meta.title.as_deref(), Some("The Rust Programming Language"));
+        assert_eq!(meta.description.as_deref(), Some("A book about Rust"));
+        assert_eq!(meta.keywords, vec!["rust", "programming-languages"]);
+
+        let meta = parse(r#"<title>Page</title><meta property="og:title" content="Better">"#);
+        assert_eq!(meta.title.as_deref(), Some("Better"));
+    }
+
+    #[test]
+    fn test_fetch() {
+        let server = TestServer::start(vec![("/book", TestServer::page(200, PAGE))]);
+        let agent = net::agent(net::DEFAULT_TIMEOUT, net::DEFAULT_MAX_REDIRECTS);
+
+        let meta = fetch(&agent, &server.url("/book")).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("The Rust Programming Language"));
+        assert!(fetch(&agent, &server.url("/missing")).is_err());
+    }
+
+    #[test]
+    fn test_name_from_url() {
+        let name = |url: &str| name_from_url(&Url::parse(url).unwrap());
+        assert_eq!(name("https://www.example.com/"), "example.com");
+        assert_eq!(
+            name("https://example.com/docs/getting-started.html"),
+            "example.com - getting started"
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! HTTP access for commands that look at the pages bookmarks point to.
+
+pub mod archive;
+pub mod check;
+pub mod meta;
+#[cfg(test)]
+pub mod test_server;
+
+use std::time::Duration;
+
+use ureq::Agent;
+
+/// The default time allowed for a whole request, including redirects.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default number of redirects followed before giving up.
+pub const DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+/// Creates an HTTP agent that reports error statuses as responses rather than errors.
+pub fn agent(timeout: Duration, max_redirects: u32) -> Agent {
+    Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .max_redirects(max_redirects)
+        .http_status_as_error(false)
+        .save_redirect_history(true)
+        .user_agent(concat!("inno/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .into()
+}
@@ -0,0 +1,152 @@
+//! A minimal local HTTP server standing in for real websites in tests.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// A canned response for a path.
+#[derive(Clone)]
+pub struct Route {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    head: bool,
+}
+
+impl Route {
+    /// Answers `HEAD` requests with `405 Method Not Allowed`, like some real servers do.
+    pub fn without_head(mut self) -> Self {
+        self.head = false;
+        self
+    }
+
+    /// Sets a response header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// A server answering requests on a local port from a fixed set of routes.
+pub struct TestServer {
+    base: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Starts a server in the background. Unknown paths are answered with `404`.
+    pub fn start(routes: Vec<(&str, Route)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<Vec<(String, Route)>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(path, route)| (path.to_string(), route))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = routes.clone();
+                let log = log.clone();
+                thread::spawn(move || Self::handle(stream, &routes, &log));
+            }
+        });
+
+        Self { base, requests }
+    }
+
+    fn handle(stream: TcpStream, routes: &[(String, Route)], log: &Mutex<Vec<String>>) {
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).is_err() {
+            return;
+        }
+        let mut line = String::new();
+        while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+            line.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default();
+        log.lock().unwrap().push(format!("{} {}", method, path));
+
+        let not_found = TestServer::page(404, "not found");
+        let route = routes
+            .iter()
+            .find(|(p, _)| p == path)
+            .map_or(&not_found, |(_, route)| route);
+
+        let (status, body) = match method {
+            "HEAD" if !route.head => (405, &[][..]),
+            "HEAD" => (route.status, &[][..]),
+            _ => (route.status, &route.body[..]),
+        };
+
+        let mut response = format!(
+            "HTTP/1.1 {} Status\r\nConnection: close\r\nContent-Length: {}\r\n",
+            status,
+            if method == "HEAD" {
+                route.body.len()
+            } else {
+                body.len()
+            }
+        );
+        for (name, value) in &route.headers {
+            response.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        response.push_str("\r\n");
+
+        let mut stream = &stream;
+        let _ = stream.write_all(response.as_bytes());
+        let _ = stream.write_all(body);
+    }
+
+    /// A page with a status and body.
+    pub fn page(status: u16, body: &str) -> Route {
+        Route {
+            status,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "text/html; charset=utf-8".to_string(),
+            )],
+            body: body.as_bytes().to_vec(),
+            head: true,
+        }
+    }
+
+    /// A redirect to another path on this server.
+    pub fn redirect(status: u16, location: &str) -> Route {
+        Route {
+            status,
+            headers: vec![("Location".to_string(), location.to_string())],
+            body: Vec::new(),
+            head: true,
+        }
+    }
+
+    /// Returns the full URL of a path on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    /// Returns the requests received so far, as `METHOD /path`.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns a URL on a local port that nothing is listening on.
+    pub fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind unused port");
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}/", addr)
+    }
+}
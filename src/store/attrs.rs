@@ -0,0 +1,150 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::entry::EntryParseError;
+
+/// Extra `key=value` attributes of an entry, written as `{key=value; key="quoted value"}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attrs(Vec<(String, String)>);
+
+impl Attrs {
+    /// Create an empty set of attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an attribute.
+    pub fn push(&mut self, key: &str, value: impl ToString) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+
+    /// Returns `true` if there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the attributes in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Returns `true` if a value can be written without quotes.
+fn is_bare(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\\' | ';' | '{' | '}' | '='))
+}
+
+impl Display for Attrs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            if is_bare(value) {
+                write!(f, "{}={}", key, value)?;
+                continue;
+            }
+            write!(f, "{}=\"", key)?;
+            for c in value.chars() {
+                match c {
+                    '"' => write!(f, "\\\"")?,
+                    '\\' => write!(f, "\\\\")?,
+                    '\n' => write!(f, "\\n")?,
+                    '\r' => write!(f, "\\r")?,
+                    '\t' => write!(f, "\\t")?,
+                    c => write!(f, "{}", c)?,
+                }
+            }
+            write!(f, "\"")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl FromStr for Attrs {
+    type Err = EntryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || EntryParseError::MalformedAttributes(s.to_string());
+
+        let inner = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(malformed)?;
+
+        let mut attrs = Attrs::new();
+        let mut chars = inner.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let key: String = std::iter::from_fn(|| chars.next_if(|&c| c != '=')).collect();
+            let key = key.trim();
+            if key.is_empty() || chars.next() != Some('=') {
+                return Err(malformed());
+            }
+
+            let mut value = String::new();
+            if chars.next_if_eq(&'"').is_some() {
+                loop {
+                    match chars.next().ok_or_else(malformed)? {
+                        '"' => break,
+                        '\\' => value.push(match chars.next().ok_or_else(malformed)? {
+                            'n' => '\n',
+                            'r' => '\r',
+                            't' => '\t',
+                            c => c,
+                        }),
+                        c => value.push(c),
+                    }
+                }
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            } else {
+                value.extend(std::iter::from_fn(|| chars.next_if(|&c| c != ';')));
+                value.truncate(value.trim_end().len());
+            }
+
+            attrs.push(key, value);
+
+            match chars.next() {
+                Some(';') | None => {}
+                Some(_) => return Err(malformed()),
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut attrs = Attrs::new();
+        attrs.push("created", "2026-01-01T00:00:00Z");
+        attrs.push("note", "a \"quoted\"; value\nover two lines");
+
+        let text = attrs.to_string();
+        assert_eq!(
+            text,
+            r#"{created=2026-01-01T00:00:00Z; note="a \"quoted\"; value\nover two lines"}"#
+        );
+        assert_eq!(text.parse::<Attrs>().unwrap(), attrs);
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!("{created}".parse::<Attrs>().is_err());
+        assert!("{note=\"unterminated}".parse::<Attrs>().is_err());
+        assert!("created=1".parse::<Attrs>().is_err());
+    }
+}
@@ -1,15 +1,89 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{
+    attrs::Attrs,
+    entry::{EntryParseError, escape_name},
+    url::Url,
+};
+
+/// The outcome of the last time a bookmark's URL was checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkStatus {
+    /// The server answered with this HTTP status code.
+    Http(u16),
+    /// The request failed (e.g. DNS failure, refused connection, timeout).
+    Failed,
+}
+
+impl LinkStatus {
+    /// Returns `true` if the link should be considered dead.
+    pub fn is_dead(&self) -> bool {
+        match self {
+            LinkStatus::Http(code) => *code >= 400,
+            LinkStatus::Failed => true,
+        }
+    }
+}
+
+impl Display for LinkStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkStatus::Http(code) => write!(f, "{}", code),
+            LinkStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl FromStr for LinkStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "failed" => Ok(LinkStatus::Failed),
+            code => code.parse().map(LinkStatus::Http).map_err(|_| ()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Bookmark {
     pub name: String,
-    pub url: String,
+    pub url: Url,
     pub tags: Vec<String>,
+    pub created: Option<DateTime<Utc>>,
+    /// When the bookmark was last changed.
+    pub modified: Option<DateTime<Utc>>,
+    /// A free-form, possibly multi-line note about the page.
+    pub description: Option<String>,
+    /// When the URL was last checked, and the outcome.
+    pub checked: Option<DateTime<Utc>>,
+    pub status: Option<LinkStatus>,
+    /// Where the URL ended up after following redirects, if that differs from the URL.
+    pub final_url: Option<Url>,
+    /// The content hash of the page's archived copy.
+    pub archive: Option<String>,
+    /// Former URLs of the bookmark, still matched by queries.
+    pub aliases: Vec<Url>,
+    /// How many times, and when last, the bookmark was opened from inno.
+    pub visits: u32,
+    pub visited: Option<DateTime<Utc>>,
+    /// The collection the bookmark is filed under, or `None` at the top level.
+    ///
+    /// This comes from the section the bookmark is in within the store file, not an attribute.
+    pub collection: Option<String>,
 }
 
 impl Bookmark {
     /// Create a new bookmark.
-    pub fn new(name: String, url: String, tags: Vec<String>) -> Self {
-        Self { name, url, tags }
+    pub fn new(name: String, url: Url, tags: Vec<String>) -> Self {
+        Self {
+            name,
+            url,
+            tags,
+            ..Default::default()
+        }
     }
 
     /// Return the bookmark's name.
@@ -18,7 +92,7 @@ impl Bookmark {
     }
 
     /// Return the bookmark's URL.
-    pub fn url(&self) -> &str {
+    pub fn url(&self) -> &Url {
         &self.url
     }
 
@@ -33,7 +107,7 @@ impl Bookmark {
     }
 
     /// Sets the bookmark's URL.
-    pub fn set_url(&mut self, url: String) {
+    pub fn set_url(&mut self, url: Url) {
         self.url = url;
     }
 
@@ -42,11 +116,122 @@ impl Bookmark {
         self.tags = tags;
     }
 
+    /// Return the time the bookmark was created, if known.
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        self.created
+    }
+
+    /// Return the time the bookmark was last changed, if known.
+    pub fn modified(&self) -> Option<DateTime<Utc>> {
+        self.modified
+    }
+
+    /// Return the time the bookmark was last opened, if ever.
+    pub fn visited(&self) -> Option<DateTime<Utc>> {
+        self.visited
+    }
+
+    /// Return the bookmark's attributes as written after its tags in the store file.
+    pub fn attrs(&self) -> Attrs {
+        let mut attrs = Attrs::new();
+        if let Some(created) = self.created {
+            attrs.push("created", format_time(created));
+        }
+        if let Some(modified) = self.modified {
+            attrs.push("modified", format_time(modified));
+        }
+        if let Some(description) = &self.description {
+            attrs.push("description", description);
+        }
+        if let Some(checked) = self.checked {
+            attrs.push("checked", format_time(checked));
+        }
+        if let Some(status) = self.status {
+            attrs.push("status", status);
+        }
+        if let Some(final_url) = &self.final_url {
+            attrs.push("final", final_url.as_str());
+        }
+        if let Some(archive) = &self.archive {
+            attrs.push("archive", archive);
+        }
+        for alias in &self.aliases {
+            attrs.push("alias", alias.as_str());
+        }
+        if self.visits > 0 {
+            attrs.push("visits", self.visits);
+        }
+        if let Some(visited) = self.visited {
+            attrs.push("visited", format_time(visited));
+        }
+        attrs
+    }
+
+    /// Returns the bookmark as a line of the store file, which leaves out its collection.
+    ///
+    /// The file holds timestamps to the second, so comparing lines rather than bookmarks tells
+    /// whether two bookmarks would be saved alike.
+    pub fn line(&self) -> String {
+        let mut line = format!(
+            "{}: {} [{}]",
+            escape_name(&self.name),
+            self.url,
+            self.tags.join(", ")
+        );
+        let attrs = self.attrs();
+        if !attrs.is_empty() {
+            line.push_str(&format!(" {}", attrs));
+        }
+        line
+    }
+
+    /// Sets a single attribute read from the store file.
+    pub fn set_attr(&mut self, key: &str, value: &str) -> Result<(), EntryParseError> {
+        match key {
+            "created" => self.created = Some(parse_time(key, value)?),
+            "modified" => self.modified = Some(parse_time(key, value)?),
+            "description" => self.description = Some(value.to_string()),
+            "checked" => self.checked = Some(parse_time(key, value)?),
+            "status" => {
+                self.status = Some(value.parse().map_err(|_| {
+                    EntryParseError::InvalidAttribute(key.to_string(), value.to_string())
+                })?)
+            }
+            "final" => self.final_url = Some(value.into()),
+            "archive" => self.archive = Some(value.to_string()),
+            "alias" => self.aliases.push(value.into()),
+            "visits" => {
+                self.visits = value.parse().map_err(|_| {
+                    EntryParseError::InvalidAttribute(key.to_string(), value.to_string())
+                })?
+            }
+            "visited" => self.visited = Some(parse_time(key, value)?),
+            _ => return Err(EntryParseError::UnknownAttribute(key.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Replaces the bookmark's URL, keeping the old one as an alias.
+    pub fn rewrite_url(&mut self, url: Url) {
+        let old = std::mem::replace(&mut self.url, url);
+        if old != self.url && !self.aliases.contains(&old) {
+            self.aliases.push(old);
+        }
+        self.aliases.retain(|alias| *alias != self.url);
+    }
+
     /// Checks if the bookmark matches a fuzzy query.
     pub fn matches(&self, query: &str) -> bool {
-        self.to_string()
-            .to_lowercase()
-            .contains(&query.to_lowercase())
+        let query = query.to_lowercase();
+        self.to_string().to_lowercase().contains(&query)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|description| description.to_lowercase().contains(&query))
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.to_string().to_lowercase().contains(&query))
     }
 }
 
@@ -55,3 +240,15 @@ impl Display for Bookmark {
         write!(f, "{}: {} [{}]", self.name, self.url, self.tags.join(", "))
     }
 }
+
+/// Formats a timestamp as written in the store file.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Parses a timestamp attribute from the store file.
+fn parse_time(key: &str, value: &str) -> Result<DateTime<Utc>, EntryParseError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| EntryParseError::InvalidAttribute(key.to_string(), value.to_string()))
+}
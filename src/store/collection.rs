@@ -0,0 +1,106 @@
+//! Collection paths: named, nested groups of bookmarks such as `Work/Rust`.
+//!
+//! A collection is `None` at the top level and `Some("Work/Rust")` below it.
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CollectionError {
+    #[error("Invalid collection name: '{0}' (names cannot contain ':', '[' or ']')")]
+    InvalidName(String),
+    #[error("'..' goes above the top level")]
+    AboveTop,
+}
+
+/// Resolves a path relative to a collection.
+///
+/// Paths starting with '/' are absolute; `..` is the parent collection and `.` the collection
+/// itself. Empty components are ignored, so `/` is the top level.
+pub fn resolve(current: Option<&str>, path: &str) -> Result<Option<String>, CollectionError> {
+    let mut components: Vec<&str> = match current {
+        Some(current) if !path.starts_with('/') => current.split('/').collect(),
+        _ => Vec::new(),
+    };
+
+    for component in path.split('/').map(str::trim) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop().ok_or(CollectionError::AboveTop)?;
+            }
+            name if name.contains([':', '[', ']']) => {
+                return Err(CollectionError::InvalidName(name.to_string()));
+            }
+            name => components.push(name),
+        }
+    }
+
+    Ok((!components.is_empty()).then(|| components.join("/")))
+}
+
+/// Returns `true` if `collection` is `ancestor` or one of its descendants.
+pub fn within(collection: Option<&str>, ancestor: Option<&str>) -> bool {
+    match (collection, ancestor) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(collection), Some(ancestor)) => {
+            collection == ancestor
+                || collection
+                    .strip_prefix(ancestor)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+    }
+}
+
+/// Returns the parent of a collection, or `None` for a top-level collection.
+pub fn parent(collection: &str) -> Option<&str> {
+    collection.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// Returns the last component of a collection's path.
+pub fn name(collection: &str) -> &str {
+    collection.rsplit('/').next().unwrap_or(collection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(None, "Work/Rust").unwrap().as_deref(),
+            Some("Work/Rust")
+        );
+        assert_eq!(
+            resolve(Some("Work"), "Rust/").unwrap().as_deref(),
+            Some("Work/Rust")
+        );
+        assert_eq!(
+            resolve(Some("Work/Rust"), "..").unwrap().as_deref(),
+            Some("Work")
+        );
+        assert_eq!(
+            resolve(Some("Work/Rust"), "../../Home").unwrap().as_deref(),
+            Some("Home")
+        );
+        assert_eq!(resolve(Some("Work"), "/").unwrap(), None);
+        assert_eq!(
+            resolve(Some("Work"), "/Home/./Music").unwrap().as_deref(),
+            Some("Home/Music")
+        );
+        assert_eq!(resolve(None, ".."), Err(CollectionError::AboveTop));
+        assert!(resolve(None, "a:b").is_err());
+    }
+
+    #[test]
+    fn test_within() {
+        assert!(within(Some("Work/Rust"), Some("Work")));
+        assert!(within(Some("Work"), Some("Work")));
+        assert!(!within(Some("Workshop"), Some("Work")));
+        assert!(!within(None, Some("Work")));
+        assert!(within(None, None));
+        assert_eq!(parent("Work/Rust"), Some("Work"));
+        assert_eq!(name("Work/Rust"), "Rust");
+    }
+}
@@ -2,12 +2,20 @@ use thiserror::Error;
 
 use std::{fmt::Display, str::FromStr};
 
-use super::Bookmark;
+use super::{
+    Bookmark,
+    attrs::Attrs,
+    collection::{self, CollectionError},
+    url::{Url, UrlPolicy},
+};
 
 /// An entry in the store.
+#[derive(Clone)]
 pub enum Entry {
-    Bookmark(Bookmark),
+    Bookmark(Box<Bookmark>),
     Comment(String),
+    /// A `[Path/Sub]` header; the bookmarks after it belong to that collection (`[/]` is the top).
+    Section(Option<String>),
     Empty,
 }
 
@@ -15,7 +23,7 @@ impl Entry {
     /// If this `Entry` is a `Bookmark`, extract it and consume `self`.
     pub fn into_bookmark(self) -> Option<Bookmark> {
         match self {
-            Entry::Bookmark(bookmark) => Some(bookmark),
+            Entry::Bookmark(bookmark) => Some(*bookmark),
             _ => None,
         }
     }
@@ -40,11 +48,51 @@ impl Entry {
 impl Display for Entry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Entry::Bookmark(bookmark) => write!(f, "{}", bookmark),
-            Entry::Comment(comment) => write!(f, "{}", comment),
-            Entry::Empty => write!(f, "Empty"),
+            Entry::Bookmark(bookmark) => f.write_str(&bookmark.line()),
+            Entry::Comment(comment) => write!(f, "# {}", comment),
+            Entry::Section(collection) => {
+                write!(f, "[{}]", collection.as_deref().unwrap_or("/"))
+            }
+            Entry::Empty => Ok(()),
+        }
+    }
+}
+
+/// Escapes a bookmark name so that a ':' or a leading '#' or '[' is not mistaken for syntax.
+pub(super) fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        match c {
+            '\\' | ':' => escaped.push('\\'),
+            '#' | '[' if i == 0 => escaped.push('\\'),
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Splits a bookmark line at the first unescaped ':', unescaping the name before it.
+fn split_name(line: &str) -> Option<(String, &str)> {
+    let mut name = String::new();
+    let mut chars = line.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ':' => return Some((name, &line[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, c @ ('\\' | ':' | '#' | '['))) => name.push(c),
+                Some((_, c)) => {
+                    name.push('\\');
+                    name.push(c);
+                }
+                None => name.push('\\'),
+            },
+            c => name.push(c),
         }
     }
+
+    None
 }
 
 #[derive(Error, Debug)]
@@ -57,12 +105,25 @@ pub enum EntryParseError {
     EmptyUrl,
     #[error("Missing closing ']' for tags")]
     MissingClosingBracket,
+    #[error("Malformed attributes: '{0}'")]
+    MalformedAttributes(String),
+    #[error("Unknown attribute: '{0}'")]
+    UnknownAttribute(String),
+    #[error("Invalid value for attribute '{0}': '{1}'")]
+    InvalidAttribute(String, String),
+    #[error("URL has no scheme (e.g. 'https://'): '{0}'")]
+    MissingScheme(String),
+    #[error("Invalid URL '{0}': {1}")]
+    InvalidUrl(String, url::ParseError),
+    #[error("Unknown URL policy: '{0}'")]
+    UnknownUrlPolicy(String),
+    #[error(transparent)]
+    InvalidCollection(#[from] CollectionError),
 }
 
-impl FromStr for Entry {
-    type Err = EntryParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Entry {
+    /// Parses a line of the store file, validating bookmark URLs according to a policy.
+    pub fn parse(s: &str, policy: UrlPolicy) -> Result<Self, EntryParseError> {
         let line = s.trim();
 
         if let Some(comment) = line.strip_prefix('#') {
@@ -73,7 +134,16 @@ impl FromStr for Entry {
             return Ok(Entry::Empty);
         }
 
-        let Some((name_part, url_part)) = line.split_once(':') else {
+        // Bookmark lines always have a ':' before their URL, so a section header cannot
+        if let Some(path) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+            .filter(|path| !path.contains(':'))
+        {
+            return Ok(Entry::Section(collection::resolve(None, path)?));
+        }
+
+        let Some((name_part, url_part)) = split_name(line) else {
             return Err(EntryParseError::MissingSeparator(line.to_string()));
         };
 
@@ -85,6 +155,7 @@ impl FromStr for Entry {
         let url_part = url_part.trim();
         let mut url = url_part.to_string();
         let mut tags = Vec::new();
+        let mut attrs = None;
 
         if let Some(start_index) = url_part.find('[') {
             let extracted_url = &url_part[..start_index].trim();
@@ -93,10 +164,25 @@ impl FromStr for Entry {
             }
             url = extracted_url.to_string();
 
-            let Some(end_index) = url_part.rfind(']') else {
+            // Tags end at the first ']' that is followed by nothing but an attribute block
+            let end_index = url_part
+                .match_indices(']')
+                .map(|(i, _)| i)
+                .filter(|&i| i > start_index)
+                .find(|&i| {
+                    let rest = url_part[i + 1..].trim_start();
+                    rest.is_empty() || rest.starts_with('{')
+                })
+                .or_else(|| url_part.rfind(']').filter(|&i| i > start_index));
+            let Some(end_index) = end_index else {
                 return Err(EntryParseError::MissingClosingBracket);
             };
 
+            let rest = url_part[end_index + 1..].trim();
+            if rest.starts_with('{') {
+                attrs = Some(rest.parse::<Attrs>()?);
+            }
+
             let tags_str = &url_part[start_index + 1..end_index].trim();
             if !tags_str.is_empty() {
                 tags = tags_str
@@ -111,6 +197,70 @@ impl FromStr for Entry {
             return Err(EntryParseError::EmptyUrl);
         }
 
-        Ok(Entry::Bookmark(Bookmark { name, url, tags }))
+        let url = Url::with_policy(&url, policy)?;
+        let mut bookmark = Bookmark::new(name, url, tags);
+        for (key, value) in attrs.iter().flat_map(Attrs::iter) {
+            bookmark.set_attr(key, value)?;
+        }
+
+        Ok(Entry::Bookmark(Box::new(bookmark)))
+    }
+}
+
+impl FromStr for Entry {
+    type Err = EntryParseError;
+
+    /// Parses a line of the store file, keeping invalid URLs verbatim.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Entry::parse(s, UrlPolicy::Warn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::bookmark::LinkStatus;
+
+    #[test]
+    fn test_round_trip() {
+        let mut bookmark = Bookmark::new(
+            "#1 Rust: a \\ language".to_string(),
+            "https://www.rust-lang.org/".into(),
+            vec!["rust".to_string(), "lang".to_string()],
+        );
+        bookmark.status = Some(LinkStatus::Http(200));
+
+        for entry in [
+            Entry::Bookmark(Box::new(bookmark)),
+            Entry::Comment("A comment".to_string()),
+            Entry::Section(Some("Work/Rust".to_string())),
+            Entry::Section(None),
+            Entry::Empty,
+        ] {
+            let line = entry.to_string();
+            let parsed: Entry = line.parse().unwrap();
+            assert_eq!(parsed.to_string(), line);
+            assert_eq!(
+                parsed.bookmark().map(|b| b.name.as_str()),
+                entry.bookmark().map(|b| b.name.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_escapes() {
+        let entry: Entry = r"C\:\\Users: file:///c:/users []".parse().unwrap();
+        assert_eq!(entry.bookmark().unwrap().name, r"C:\Users");
+
+        // Backslashes that do not escape anything are kept as they are
+        let entry: Entry = r"a\b: https://example.com []".parse().unwrap();
+        assert_eq!(entry.bookmark().unwrap().name, r"a\b");
+
+        // A name starting with '[' is escaped so that it is not read as a section header
+        let bookmark = Bookmark::new("[draft]".to_string(), "https://example.com".into(), vec![]);
+        let line = Entry::Bookmark(Box::new(bookmark)).to_string();
+        assert!(line.starts_with(r"\[draft]"));
+        let entry: Entry = line.parse().unwrap();
+        assert_eq!(entry.bookmark().unwrap().name, "[draft]");
     }
 }
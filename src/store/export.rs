@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use itertools::Itertools;
+
+use super::{Store, entry::Entry, refs::BookmarkRef};
+
+use super::query::SortOrder;
+
+/// Options for rendering a store as Markdown.
+pub struct MarkdownOptions {
+    /// The top-level heading of the document.
+    pub title: String,
+    /// The order of bookmarks within each tag.
+    pub sort: SortOrder,
+    /// Whether to list bookmarks without tags under their own heading.
+    pub untagged: bool,
+    /// Whether to treat `/` in tags as a hierarchy of nested headings.
+    pub nested: bool,
+    /// Whether to include comments as prose before the bookmark lists.
+    pub comments: bool,
+    /// The bookmarks to include, if not all of them.
+    pub selection: Option<Vec<usize>>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            title: "Bookmarks".to_string(),
+            sort: SortOrder::default(),
+            untagged: false,
+            nested: false,
+            comments: true,
+            selection: None,
+        }
+    }
+}
+
+/// A heading in the exported document and the bookmarks listed under it.
+#[derive(Default)]
+struct Group<'a> {
+    bookmarks: Vec<BookmarkRef<'a>>,
+    children: BTreeMap<String, Group<'a>>,
+}
+
+impl<'a> Group<'a> {
+    /// Returns the group at the given path, creating it if necessary.
+    fn descend(&mut self, path: &[&str]) -> &mut Group<'a> {
+        path.iter().fold(self, |group, part| {
+            group.children.entry(part.to_string()).or_default()
+        })
+    }
+
+    fn sort(&mut self, order: SortOrder) {
+        order.sort(&mut self.bookmarks);
+        self.children
+            .values_mut()
+            .for_each(|child| child.sort(order));
+    }
+
+    fn write(&self, out: &mut String, title: &str, level: usize) {
+        let _ = writeln!(out, "{} {}\n", "#".repeat(level.min(6)), title);
+
+        for bookmark in &self.bookmarks {
+            let _ = writeln!(out, "- {}", link(bookmark));
+            // Indented lines continue the list item
+            for line in bookmark.description.iter().flat_map(|d| d.lines()) {
+                let _ = writeln!(out, "  {}", line);
+            }
+        }
+        if !self.bookmarks.is_empty() {
+            out.push('\n');
+        }
+
+        for (name, child) in &self.children {
+            child.write(out, name, level + 1);
+        }
+    }
+}
+
+/// Renders a bookmark as a Markdown link.
+fn link(bookmark: &BookmarkRef) -> String {
+    let name = bookmark
+        .name
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+
+    if bookmark
+        .url
+        .as_str()
+        .contains(|c: char| c.is_whitespace() || c == '(' || c == ')')
+    {
+        format!("[{}](<{}>)", name, bookmark.url)
+    } else {
+        format!("[{}]({})", name, bookmark.url)
+    }
+}
+
+/// Renders consecutive comments as paragraphs, separated by empty entries or bookmarks.
+fn prose(store: &Store) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for entry in store.entries.iter().flatten() {
+        match entry {
+            Entry::Comment(comment) if !comment.is_empty() => current.push(comment),
+            _ if !current.is_empty() => {
+                paragraphs.push(current.join(" "));
+                current.clear();
+            }
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join(" "));
+    }
+
+    paragraphs
+}
+
+/// Renders the bookmarks in a store as a Markdown document, grouped under a heading per tag.
+pub fn markdown(store: &Store, options: &MarkdownOptions) -> String {
+    let mut root = Group::default();
+    let mut untagged = Group::default();
+
+    let selected = |id: &usize| {
+        options
+            .selection
+            .as_ref()
+            .is_none_or(|selection| selection.contains(id))
+    };
+    for bookmark in store.iter().filter(|bookmark| selected(&bookmark.id)) {
+        let mut grouped = false;
+        for tag in bookmark.tags.iter().unique() {
+            let path: Vec<&str> = if options.nested {
+                tag.split('/').filter(|part| !part.is_empty()).collect()
+            } else {
+                vec![tag.as_str()]
+            };
+            // A tag such as "/" names no group; the bookmark is untagged unless another tag does
+            if path.is_empty() {
+                continue;
+            }
+            root.descend(&path).bookmarks.push(bookmark);
+            grouped = true;
+        }
+
+        if !grouped {
+            untagged.bookmarks.push(bookmark);
+        }
+    }
+
+    root.sort(options.sort);
+    untagged.sort(options.sort);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", options.title);
+
+    if options.comments {
+        for paragraph in prose(store) {
+            let _ = writeln!(out, "{}\n", paragraph);
+        }
+    }
+
+    for (name, group) in &root.children {
+        group.write(&mut out, name, 2);
+    }
+
+    if options.untagged && !untagged.bookmarks.is_empty() {
+        untagged.write(&mut out, "Untagged", 2);
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::bookmark::Bookmark;
+
+    fn mock_store() -> Store {
+        let mut store = Store::new();
+        store.add_entry(Entry::Comment("Links we find useful.".to_string()));
+        store.add_entry(Entry::Empty);
+        store.add(Bookmark::new(
+            "Rust".to_string(),
+            "https://www.rust-lang.org".into(),
+            vec!["lang/rust".to_string()],
+        ));
+        let mut go = Bookmark::new(
+            "Go".to_string(),
+            "https://go.dev".into(),
+            vec!["lang".to_string()],
+        );
+        go.description = Some("Simple, fast.\nGood for tools.".to_string());
+        store.add(go);
+        store.add(Bookmark::new(
+            "Example".to_string(),
+            "https://example.com".into(),
+            vec![],
+        ));
+        store
+    }
+
+    #[test]
+    fn test_markdown_flat() {
+        let options = MarkdownOptions {
+            sort: SortOrder::Name,
+            ..Default::default()
+        };
+        let out = markdown(&mock_store(), &options);
+
+        assert_eq!(
+            out,
+            "# Bookmarks\n\n\
+             Links we find useful.\n\n\
+             ## lang\n\n\
+             - [Go](https://go.dev/)\n  Simple, fast.\n  Good for tools.\n\n\
+             ## lang/rust\n\n\
+             - [Rust](https://www.rust-lang.org/)\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_nested_untagged() {
+        let options = MarkdownOptions {
+            untagged: true,
+            nested: true,
+            comments: false,
+            ..Default::default()
+        };
+        let out = markdown(&mock_store(), &options);
+
+        assert_eq!(
+            out,
+            "# Bookmarks\n\n\
+             ## lang\n\n\
+             - [Go](https://go.dev/)\n  Simple, fast.\n  Good for tools.\n\n\
+             ### rust\n\n\
+             - [Rust](https://www.rust-lang.org/)\n\n\
+             ## Untagged\n\n\
+             - [Example](https://example.com/)\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_nested_empty_path() {
+        let mut store = Store::new();
+        store.add(Bookmark::new(
+            "Root".to_string(),
+            "https://example.com".into(),
+            vec!["/".to_string()],
+        ));
+        let options = MarkdownOptions {
+            untagged: true,
+            nested: true,
+            ..Default::default()
+        };
+        let out = markdown(&store, &options);
+
+        assert_eq!(
+            out,
+            "# Bookmarks\n\n\
+             ## Untagged\n\n\
+             - [Root](https://example.com/)\n"
+        );
+    }
+}
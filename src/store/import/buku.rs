@@ -0,0 +1,90 @@
+//! Reads buku's SQLite bookmark database.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+
+use super::{Format, clean_tag, is_internal};
+use crate::store::bookmark::Bookmark;
+
+/// Reads all bookmarks from a buku database (usually `~/.local/share/buku/bookmarks.db`).
+///
+/// buku does not record when bookmarks were added, so imported bookmarks have no creation time.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open buku database")?;
+    read_connection(&connection)
+}
+
+fn read_connection(connection: &Connection) -> Result<Vec<Bookmark>> {
+    let mut statement = connection
+        .prepare("SELECT URL, metadata, tags, desc FROM bookmarks ORDER BY id")
+        .context("Not a buku database")?;
+
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let bookmarks = rows
+        .into_iter()
+        .filter(|(url, ..)| !url.trim().is_empty() && !is_internal(url))
+        .map(|(url, title, tags, desc)| {
+            let name = title
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| url.clone());
+            // buku stores tags as a comma-delimited list with leading and trailing commas
+            let tags = tags
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(clean_tag)
+                .collect();
+            let mut bookmark = Bookmark::new(name, url.into(), tags);
+            bookmark.description = desc.filter(|desc| !desc.trim().is_empty());
+            bookmark
+        })
+        .collect();
+
+    Ok(bookmarks)
+}
+
+inventory::submit!(Format {
+    name: "buku",
+    description: "buku SQLite database",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE bookmarks (id INTEGER PRIMARY KEY, URL TEXT NOT NULL UNIQUE,
+                     metadata TEXT DEFAULT '', tags TEXT DEFAULT ',', desc TEXT DEFAULT '',
+                     flags INTEGER DEFAULT 0);
+                 INSERT INTO bookmarks (URL, metadata, tags) VALUES
+                     ('https://www.rust-lang.org/', 'Rust', ',lang,rust,'),
+                     ('https://docs.rs/', '', ',');",
+            )
+            .unwrap();
+
+        let bookmarks = read_connection(&connection).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].tags, vec!["lang", "rust"]);
+        assert_eq!(bookmarks[1].name, "https://docs.rs/");
+        assert!(bookmarks[1].tags.is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! Reads the `Bookmarks` JSON file of Chromium-based browsers.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::{Format, folder_tag, is_internal};
+use crate::store::bookmark::Bookmark;
+
+/// Microseconds between 1601-01-01 (the Windows/WebKit epoch) and the Unix epoch.
+const EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+
+/// Reads all bookmarks from a Chromium `Bookmarks` file, turning folders into tags.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let text = fs::read_to_string(path).context("Failed to read bookmarks file")?;
+    parse(&text)
+}
+
+/// Parses the contents of a Chromium `Bookmarks` file.
+pub fn parse(text: &str) -> Result<Vec<Bookmark>> {
+    let json: Value = serde_json::from_str(text).context("Bookmarks file is not valid JSON")?;
+    let roots = json
+        .get("roots")
+        .and_then(Value::as_object)
+        .context("Bookmarks file has no 'roots' object")?;
+
+    let mut bookmarks = Vec::new();
+    // The roots ("Bookmarks bar", "Other bookmarks", ...) are not turned into tags
+    for root in roots.values() {
+        walk(root, &mut Vec::new(), &mut bookmarks);
+    }
+
+    Ok(bookmarks)
+}
+
+fn walk(node: &Value, path: &mut Vec<String>, bookmarks: &mut Vec<Bookmark>) {
+    let Some(children) = node.get("children").and_then(Value::as_array) else {
+        return;
+    };
+
+    for child in children {
+        let name = child
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        match child.get("type").and_then(Value::as_str) {
+            Some("folder") => {
+                path.push(name.to_string());
+                walk(child, path, bookmarks);
+                path.pop();
+            }
+            Some("url") => {
+                let Some(url) = child.get("url").and_then(Value::as_str) else {
+                    continue;
+                };
+                if is_internal(url) {
+                    continue;
+                }
+
+                let name = if name.trim().is_empty() { url } else { name };
+                let mut bookmark = Bookmark::new(
+                    name.trim().to_string(),
+                    url.into(),
+                    folder_tag(path).into_iter().collect(),
+                );
+                bookmark.created = child
+                    .get("date_added")
+                    .and_then(Value::as_str)
+                    .and_then(|date| date.parse::<i64>().ok())
+                    .and_then(webkit_time);
+                bookmarks.push(bookmark);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts a WebKit timestamp (microseconds since 1601-01-01) to a UTC time.
+fn webkit_time(micros: i64) -> Option<DateTime<Utc>> {
+    if micros <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp_micros(micros - EPOCH_OFFSET_MICROS)
+}
+
+inventory::submit!(Format {
+    name: "chrome",
+    description: "Chromium Bookmarks JSON file",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"{
+            "roots": {
+                "bookmark_bar": {
+                    "type": "folder",
+                    "name": "Bookmarks bar",
+                    "children": [
+                        { "type": "url", "name": "Rust", "url": "https://www.rust-lang.org/",
+                          "date_added": "13350000000000000" },
+                        { "type": "folder", "name": "Dev", "children": [
+                            { "type": "folder", "name": "Docs", "children": [
+                                { "type": "url", "name": "", "url": "https://docs.rs/" }
+                            ] },
+                            { "type": "url", "name": "Settings", "url": "chrome://settings" }
+                        ] }
+                    ]
+                },
+                "other": { "type": "folder", "name": "Other bookmarks", "children": [] }
+            },
+            "version": 1
+        }"#;
+
+        let bookmarks = parse(text).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert!(bookmarks[0].tags.is_empty());
+        assert_eq!(
+            bookmarks[0].created.unwrap().to_rfc3339(),
+            "2024-01-17T21:20:00+00:00"
+        );
+
+        assert_eq!(bookmarks[1].name, "https://docs.rs/");
+        assert_eq!(bookmarks[1].tags, vec!["Dev/Docs"]);
+    }
+}
@@ -0,0 +1,242 @@
+//! Reads the `places.sqlite` database of Firefox profiles.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use rusqlite::{Connection, OpenFlags};
+
+use super::{Format, clean_tag, folder_tag, is_internal};
+use crate::store::bookmark::Bookmark;
+
+/// `moz_bookmarks.type` of a bookmark row.
+const TYPE_BOOKMARK: i64 = 1;
+/// `moz_bookmarks.type` of a folder row.
+const TYPE_FOLDER: i64 = 2;
+
+/// GUID of the folder that holds one sub-folder per tag.
+const TAGS_ROOT: &str = "tags________";
+
+/// A row of `moz_bookmarks`, joined with its place.
+struct Row {
+    id: i64,
+    kind: i64,
+    parent: Option<i64>,
+    place: Option<i64>,
+    title: Option<String>,
+    guid: String,
+    url: Option<String>,
+    added: Option<i64>,
+}
+
+/// Reads all bookmarks from a copy of a Firefox `places.sqlite` database.
+///
+/// Folder paths become tags, as do Firefox's own tags; keywords become `keyword:<word>` tags.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open places database")?;
+    read_connection(&connection)
+}
+
+fn read_connection(connection: &Connection) -> Result<Vec<Bookmark>> {
+    let mut statement = connection
+        .prepare(
+            "SELECT b.id, b.type, b.parent, b.fk, b.title, b.guid, p.url, b.dateAdded
+             FROM moz_bookmarks b
+             LEFT JOIN moz_places p ON p.id = b.fk
+             ORDER BY b.parent, b.position",
+        )
+        .context("Not a Firefox places database")?;
+
+    let rows = statement
+        .query_map([], |row| {
+            Ok(Row {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                parent: row.get(2)?,
+                place: row.get(3)?,
+                title: row.get(4)?,
+                guid: row.get(5)?,
+                url: row.get(6)?,
+                added: row.get(7)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // A place can have several keywords, so they are read apart rather than joined
+    let mut place_keywords: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut statement = connection
+        .prepare("SELECT place_id, keyword FROM moz_keywords ORDER BY id")
+        .context("Not a Firefox places database")?;
+    for keyword in statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (place, keyword): (i64, String) = keyword?;
+        place_keywords.entry(place).or_default().push(keyword);
+    }
+
+    let by_id: HashMap<i64, &Row> = rows.iter().map(|row| (row.id, row)).collect();
+    let tags_root = rows
+        .iter()
+        .find(|row| row.guid == TAGS_ROOT)
+        .map(|row| row.id);
+
+    // Firefox stores tags as folders under the tags root, each holding a row per tagged place
+    let mut place_tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in &rows {
+        let Some(tag) = row
+            .parent
+            .and_then(|parent| by_id.get(&parent))
+            .filter(|folder| folder.parent.is_some() && folder.parent == tags_root)
+            .and_then(|folder| folder.title.clone())
+        else {
+            continue;
+        };
+        if let Some(place) = row.place {
+            place_tags.entry(place).or_default().push(tag);
+        }
+    }
+
+    let mut bookmarks = Vec::new();
+    for row in &rows {
+        if row.kind != TYPE_BOOKMARK {
+            continue;
+        }
+        let Some(url) = row.url.as_deref().filter(|url| !is_internal(url)) else {
+            continue;
+        };
+
+        let Some(path) = folder_path(row, &by_id, tags_root) else {
+            continue; // tag membership rows, handled above
+        };
+
+        let place_tags = row.place.and_then(|place| place_tags.get(&place));
+        let keywords = row.place.and_then(|place| place_keywords.get(&place));
+        let mut seen = HashSet::new();
+        let tags: Vec<String> = folder_tag(&path)
+            .into_iter()
+            .chain(
+                place_tags
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|tag| clean_tag(tag)),
+            )
+            .chain(
+                keywords
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|keyword| clean_tag(&format!("keyword:{}", keyword))),
+            )
+            .filter(|tag| seen.insert(tag.clone()))
+            .collect();
+
+        let name = row
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(url);
+
+        let mut bookmark = Bookmark::new(name.to_string(), url.into(), tags);
+        bookmark.created = row.added.and_then(DateTime::from_timestamp_micros);
+        bookmarks.push(bookmark);
+    }
+
+    Ok(bookmarks)
+}
+
+/// Returns the titles of the folders containing a row, excluding the built-in roots.
+///
+/// Returns `None` if the row lives under the tags root.
+fn folder_path(
+    row: &Row,
+    by_id: &HashMap<i64, &Row>,
+    tags_root: Option<i64>,
+) -> Option<Vec<String>> {
+    let mut path = Vec::new();
+    let mut parent = row.parent;
+
+    while let Some(folder) = parent.and_then(|id| by_id.get(&id)) {
+        if Some(folder.id) == tags_root {
+            return None;
+        }
+        // Built-in roots (menu, toolbar, ...) sit directly below the root folder
+        let is_root = |row: &Row| row.parent.is_none_or(|id| !by_id.contains_key(&id));
+        let is_builtin = is_root(folder)
+            || folder
+                .parent
+                .and_then(|id| by_id.get(&id))
+                .is_some_and(|p| is_root(p));
+        if folder.kind == TYPE_FOLDER && !is_builtin {
+            path.push(folder.title.clone().unwrap_or_default());
+        }
+        parent = folder.parent;
+    }
+
+    path.reverse();
+    Some(path)
+}
+
+inventory::submit!(Format {
+    name: "firefox",
+    description: "Firefox places.sqlite database",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                     parent INTEGER, position INTEGER, title TEXT, dateAdded INTEGER, guid TEXT);
+
+                 INSERT INTO moz_places VALUES (1, 'https://www.rust-lang.org/', NULL),
+                     (2, 'place:sort=8', NULL), (3, 'https://docs.rs/', NULL);
+                 INSERT INTO moz_keywords VALUES (1, 'docs', 3), (2, 'rs', 3);
+
+                 INSERT INTO moz_bookmarks VALUES
+                     (1, 2, NULL, 0, 0, '', NULL, 'root________'),
+                     (2, 2, NULL, 1, 0, 'menu', NULL, 'menu________'),
+                     (3, 2, NULL, 1, 1, 'tags', NULL, 'tags________'),
+                     (4, 2, NULL, 2, 0, 'Dev', NULL, 'a'),
+                     (5, 1, 1, 2, 1, 'Rust', 1700000000000000, 'b'),
+                     (6, 3, NULL, 2, 2, NULL, NULL, 'c'),
+                     (7, 1, 2, 2, 3, 'Recent', NULL, 'd'),
+                     (8, 1, 3, 4, 0, 'Docs', NULL, 'e'),
+                     (9, 2, NULL, 3, 0, 'reference', NULL, 'f'),
+                     (10, 1, 3, 9, 0, NULL, NULL, 'g'),
+                     (11, 2, NULL, 3, 1, ' api [docs] ', NULL, 'h'),
+                     (12, 1, 3, 11, 0, NULL, NULL, 'i'),
+                     (13, 2, NULL, 3, 2, 'Dev', NULL, 'j'),
+                     (14, 1, 3, 13, 0, NULL, NULL, 'k');",
+            )
+            .unwrap();
+
+        let bookmarks = read_connection(&connection).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert!(bookmarks[0].tags.is_empty());
+        assert_eq!(bookmarks[0].created.unwrap().timestamp(), 1_700_000_000);
+
+        assert_eq!(bookmarks[1].name, "Docs");
+        assert_eq!(
+            bookmarks[1].tags,
+            vec![
+                "Dev",
+                "reference",
+                "api -docs-",
+                "keyword:docs",
+                "keyword:rs"
+            ]
+        );
+    }
+}
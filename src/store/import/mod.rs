@@ -0,0 +1,247 @@
+//! Readers for bookmark files written by other applications.
+//!
+//! Each reader registers a [`Format`] via `inventory`, so adding a new format only requires
+//! a new module here.
+
+pub mod buku;
+pub mod chrome;
+pub mod firefox;
+pub mod pinboard;
+pub mod pocket;
+pub mod raindrop;
+
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::{Store, bookmark::Bookmark, normalize::normalize, url::Url};
+
+/// A file format that bookmarks can be imported from.
+pub struct Format {
+    /// The name of the format (e.g. "firefox", "pinboard").
+    pub name: &'static str,
+    /// A short description of the files this format reads.
+    pub description: &'static str,
+    /// The function that reads all bookmarks from a file in this format.
+    pub read: fn(&Path) -> Result<Vec<Bookmark>>,
+}
+
+impl Format {
+    /// Collects all formats registered via inventory.
+    pub fn all() -> Vec<&'static Format> {
+        inventory::iter.into_iter().collect()
+    }
+
+    /// Returns the format with the given name.
+    pub fn find(name: &str) -> Option<&'static Format> {
+        inventory::iter::<Format>
+            .into_iter()
+            .find(|format| format.name == name)
+    }
+}
+
+inventory::collect!(Format);
+
+/// An imported bookmark whose URL was already in the store.
+pub struct Merge {
+    /// The ID of the existing bookmark.
+    pub id: usize,
+    /// The tags that were added to the existing bookmark.
+    pub new_tags: Vec<String>,
+}
+
+/// A summary of what happened to each bookmark during an import.
+#[derive(Default)]
+pub struct ImportReport {
+    /// IDs of newly created bookmarks.
+    pub added: Vec<usize>,
+    /// Existing bookmarks that gained tags from an imported duplicate.
+    pub merged: Vec<Merge>,
+    /// The number of imported bookmarks that were already fully present.
+    pub unchanged: usize,
+    /// URLs refused by the store's URL policy.
+    pub rejected: Vec<String>,
+}
+
+impl Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "added: {}; merged: {}; unchanged duplicates: {}; rejected: {}",
+            self.added.len(),
+            self.merged.len(),
+            self.unchanged,
+            self.rejected.len()
+        )
+    }
+}
+
+impl Store {
+    /// Add imported bookmarks to the store, merging any whose normalized URL is already present.
+    ///
+    /// Duplicates gain the union of both sets of tags and keep the earlier creation time. URLs
+    /// that do not parse are prefixed, kept or rejected according to the store's URL policy.
+    pub fn import(&mut self, bookmarks: Vec<Bookmark>) -> ImportReport {
+        let mut report = ImportReport::default();
+        let mut by_url: HashMap<String, usize> = self
+            .iter()
+            .map(|bookmark| (normalize(bookmark.url.as_str()), bookmark.id))
+            .collect();
+
+        for mut bookmark in bookmarks {
+            if !bookmark.url.is_valid() {
+                match Url::with_policy(bookmark.url.as_str(), self.url_policy) {
+                    Ok(url) => bookmark.url = url,
+                    Err(_) => {
+                        report.rejected.push(bookmark.url.as_str().to_string());
+                        continue;
+                    }
+                }
+            }
+
+            let url = normalize(bookmark.url.as_str());
+            let Some(&id) = by_url.get(&url) else {
+                let id = self.add(bookmark);
+                by_url.insert(url, id);
+                report.added.push(id);
+                continue;
+            };
+
+            // Clean both sides first, so that case variants of known tags do not count as new
+            let existing = self.get(id).expect("indexed bookmark exists");
+            let known = self.clean_tags(existing.tags.clone());
+            let new_tags: Vec<String> = self
+                .clean_tags(bookmark.tags)
+                .into_iter()
+                .filter(|tag| !existing.tags.contains(tag) && !known.contains(tag))
+                .collect();
+            let created = match (existing.created, bookmark.created) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            let mut tags = existing.tags.clone();
+            tags.extend(new_tags.iter().cloned());
+
+            if created != existing.created {
+                self.get_mut(id).expect("indexed bookmark exists").created = created;
+            }
+
+            if new_tags.is_empty() {
+                report.unchanged += 1;
+                continue;
+            }
+
+            self.set_tags(id, tags);
+            match report.merged.iter_mut().find(|merge| merge.id == id) {
+                Some(merge) => merge.new_tags.extend(new_tags),
+                None => report.merged.push(Merge { id, new_tags }),
+            }
+        }
+
+        report
+    }
+}
+
+/// URL schemes that only have meaning inside the browser that wrote them.
+const INTERNAL_SCHEMES: &[&str] = &["place:", "chrome:", "about:", "javascript:"];
+
+/// Returns `true` if a URL points at a browser-internal page rather than a real resource.
+fn is_internal(url: &str) -> bool {
+    INTERNAL_SCHEMES.iter().any(|scheme| {
+        url.get(..scheme.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// Joins a folder path into a single hierarchical tag (e.g. `dev/rust`).
+fn folder_tag(path: &[String]) -> Option<String> {
+    let parts: Vec<_> = path
+        .iter()
+        .map(|part| part.trim().replace(['/', ',', '[', ']'], "-"))
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Cleans up a tag read from another application, returning `None` if nothing is left.
+fn clean_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace([',', '[', ']'], "-");
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Parses a Unix timestamp in seconds, as written by several services.
+fn unix_time(text: &str) -> Option<DateTime<Utc>> {
+    text.trim()
+        .parse()
+        .ok()
+        .filter(|&seconds| seconds > 0)
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+}
+
+/// Parses an RFC 3339 timestamp (e.g. `2024-01-01T12:00:00.000Z`).
+fn rfc3339_time(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_merges_duplicates() {
+        let mut store = Store::new();
+        store.create(
+            "Rust".to_string(),
+            "https://www.rust-lang.org".into(),
+            vec!["rust".to_string()],
+        );
+
+        let report = store.import(vec![
+            Bookmark::new(
+                "Rust Language".to_string(),
+                "https://www.rust-lang.org".into(),
+                vec!["rust".to_string(), "lang".to_string()],
+            ),
+            Bookmark::new("Docs".to_string(), "https://docs.rs".into(), vec![]),
+            Bookmark::new("Docs again".to_string(), "https://docs.rs".into(), vec![]),
+            Bookmark::new("Broken".to_string(), "not a url".into(), vec![]),
+        ]);
+
+        assert_eq!(report.added, vec![1]);
+        assert_eq!(report.merged.len(), 1);
+        assert_eq!(report.merged[0].id, 0);
+        assert_eq!(report.merged[0].new_tags, vec!["lang"]);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.rejected, vec!["not a url"]);
+
+        let rust = store.get(0).unwrap();
+        assert_eq!(rust.name, "Rust");
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(store.find_by_tag("lang").len(), 1);
+    }
+
+    #[test]
+    fn test_import_normalizes_merged_tags() {
+        let mut store = Store::new();
+        store.normalize_tags = true;
+        store.create(
+            "Rust".to_string(),
+            "https://www.rust-lang.org".into(),
+            vec!["Rust".to_string()],
+        );
+
+        let report = store.import(vec![Bookmark::new(
+            "Rust".to_string(),
+            "https://www.rust-lang.org".into(),
+            vec!["RUST".to_string(), "Web Dev".to_string()],
+        )]);
+
+        assert_eq!(report.merged[0].new_tags, vec!["web-dev"]);
+        assert_eq!(store.get(0).unwrap().tags, vec!["rust", "web-dev"]);
+        assert_eq!(store.find_by_tag("web-dev").len(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+//! Reads Pinboard's JSON export (`/export/format:json/`).
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use super::{Format, clean_tag, rfc3339_time};
+use crate::store::bookmark::Bookmark;
+
+/// Reads all bookmarks from a Pinboard JSON export. Unread posts are tagged `toread`.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let text = fs::read_to_string(path).context("Failed to read Pinboard export")?;
+    parse(&text)
+}
+
+/// Parses the contents of a Pinboard JSON export.
+pub fn parse(text: &str) -> Result<Vec<Bookmark>> {
+    let json: Value = serde_json::from_str(text).context("Pinboard export is not valid JSON")?;
+    let posts = json
+        .as_array()
+        .context("Pinboard export is not a list of posts")?;
+
+    let field = |post: &Value, key: &str| {
+        post.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    let mut bookmarks = Vec::new();
+    for post in posts {
+        let url = field(post, "href");
+        if url.is_empty() {
+            continue;
+        }
+
+        let name = match field(post, "description") {
+            name if name.is_empty() => url.clone(),
+            name => name,
+        };
+
+        let mut tags: Vec<String> = field(post, "tags")
+            .split_whitespace()
+            .filter_map(clean_tag)
+            .collect();
+        if field(post, "toread") == "yes" {
+            tags.push("toread".to_string());
+        }
+
+        let mut bookmark = Bookmark::new(name, url.into(), tags);
+        bookmark.created = rfc3339_time(&field(post, "time"));
+        bookmark.description = Some(field(post, "extended")).filter(|d| !d.is_empty());
+        bookmarks.push(bookmark);
+    }
+
+    Ok(bookmarks)
+}
+
+inventory::submit!(Format {
+    name: "pinboard",
+    description: "Pinboard JSON export",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"[
+            {"href":"https://www.rust-lang.org/","description":"Rust","extended":"A language",
+             "time":"2024-03-01T10:00:00Z","shared":"yes","toread":"yes","tags":"rust lang"},
+            {"href":"https://docs.rs/","description":"","time":"","toread":"no","tags":""}
+        ]"#;
+
+        let bookmarks = parse(text).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].tags, vec!["rust", "lang", "toread"]);
+        assert_eq!(bookmarks[0].created.unwrap().timestamp(), 1_709_287_200);
+        assert_eq!(bookmarks[0].description.as_deref(), Some("A language"));
+        assert_eq!(bookmarks[1].name, "https://docs.rs/");
+        assert!(bookmarks[1].tags.is_empty());
+        assert!(bookmarks[1].created.is_none());
+    }
+}
@@ -0,0 +1,85 @@
+//! Reads Pocket's HTML export (`ril_export.html`).
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use super::{Format, clean_tag, is_internal, unix_time};
+use crate::html;
+use crate::store::bookmark::Bookmark;
+
+/// Reads all bookmarks from a Pocket HTML export.
+///
+/// Pocket lists saved items under an "Unread" and a "Read Archive" heading; items from the
+/// former are tagged `toread`.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let text = fs::read_to_string(path).context("Failed to read Pocket export")?;
+    Ok(parse(&text))
+}
+
+/// Parses the contents of a Pocket HTML export.
+pub fn parse(text: &str) -> Vec<Bookmark> {
+    let archive_start = text
+        .to_ascii_lowercase()
+        .find("read archive")
+        .unwrap_or(text.len());
+    let unread = html::anchors(&text[..archive_start]).len();
+
+    html::anchors(text)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, anchor)| {
+            let url = anchor.attr("href")?.trim().to_string();
+            if url.is_empty() || is_internal(&url) {
+                return None;
+            }
+
+            let name = match anchor.text.trim() {
+                "" => url.clone(),
+                name => name.to_string(),
+            };
+
+            let mut tags: Vec<String> = anchor
+                .attr("tags")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(clean_tag)
+                .collect();
+            if i < unread {
+                tags.push("toread".to_string());
+            }
+
+            let mut bookmark = Bookmark::new(name, url.into(), tags);
+            bookmark.created = anchor.attr("time_added").and_then(unix_time);
+            Some(bookmark)
+        })
+        .collect()
+}
+
+inventory::submit!(Format {
+    name: "pocket",
+    description: "Pocket HTML export",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"<!DOCTYPE html><html><body>
+            <h1>Unread</h1>
+            <ul><li><a href="https://www.rust-lang.org/" time_added="1700000000" tags="rust,lang">Rust</a></li></ul>
+            <h1>Read Archive</h1>
+            <ul><li><a href="https://docs.rs/" time_added="1600000000" tags="">https://docs.rs/</a></li></ul>
+            </body></html>"#;
+
+        let bookmarks = parse(text);
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].tags, vec!["rust", "lang", "toread"]);
+        assert_eq!(bookmarks[0].created.unwrap().timestamp(), 1_700_000_000);
+        assert!(bookmarks[1].tags.is_empty());
+    }
+}
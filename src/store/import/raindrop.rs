@@ -0,0 +1,97 @@
+//! Reads Raindrop.io's CSV export.
+
+use std::{fs::File, path::Path};
+
+use anyhow::{Context, Result};
+
+use super::{Format, clean_tag, folder_tag, rfc3339_time};
+use crate::store::bookmark::Bookmark;
+
+/// The collection Raindrop puts bookmarks in when none was chosen.
+const UNSORTED: &str = "Unsorted";
+
+/// Reads all bookmarks from a Raindrop CSV export. Collections become tags.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    let file = File::open(path).context("Failed to open Raindrop export")?;
+    parse(file)
+}
+
+/// Parses a Raindrop CSV export.
+pub fn parse(reader: impl std::io::Read) -> Result<Vec<Bookmark>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader
+        .headers()
+        .context("Raindrop export has no header")?
+        .clone();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+
+    let url_column = column("url").context("Raindrop export has no 'url' column")?;
+    let (title, note, excerpt, folder, tags, created) = (
+        column("title"),
+        column("note"),
+        column("excerpt"),
+        column("folder"),
+        column("tags"),
+        column("created"),
+    );
+
+    let mut bookmarks = Vec::new();
+    for record in reader.records() {
+        let record = record.context("Failed to read Raindrop record")?;
+        let field =
+            |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or_default().trim();
+
+        let url = field(Some(url_column));
+        if url.is_empty() {
+            continue;
+        }
+        let name = match field(title) {
+            "" => url,
+            name => name,
+        };
+
+        let folder = field(folder);
+        let mut bookmark_tags: Vec<String> = Vec::new();
+        if folder != UNSORTED {
+            let path: Vec<String> = folder.split('/').map(str::to_string).collect();
+            bookmark_tags.extend(folder_tag(&path));
+        }
+        bookmark_tags.extend(field(tags).split(',').filter_map(clean_tag));
+
+        let mut bookmark = Bookmark::new(name.to_string(), url.into(), bookmark_tags);
+        bookmark.created = rfc3339_time(field(created));
+        bookmark.description = [field(note), field(excerpt)]
+            .into_iter()
+            .find(|text| !text.is_empty())
+            .map(str::to_string);
+        bookmarks.push(bookmark);
+    }
+
+    Ok(bookmarks)
+}
+
+inventory::submit!(Format {
+    name: "raindrop",
+    description: "Raindrop.io CSV export",
+    read,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "id,title,note,excerpt,url,folder,tags,created,cover,highlights,favorite\n\
+            1,Rust,,,https://www.rust-lang.org/,Dev/Languages,\"rust, lang\",2024-03-01T10:00:00.000Z,,,false\n\
+            2,,,,https://docs.rs/,Unsorted,,,,,false\n";
+
+        let bookmarks = parse(text.as_bytes()).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].tags, vec!["Dev/Languages", "rust", "lang"]);
+        assert_eq!(bookmarks[0].created.unwrap().timestamp(), 1_709_287_200);
+        assert_eq!(bookmarks[1].name, "https://docs.rs/");
+        assert!(bookmarks[1].tags.is_empty());
+    }
+}
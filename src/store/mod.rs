@@ -1,24 +1,38 @@
+pub mod attrs;
 pub mod bookmark;
+pub mod collection;
 pub mod entry;
+pub mod export;
+pub mod import;
+pub mod normalize;
+pub mod query;
 pub mod refs;
+pub mod sync;
+pub mod url;
 
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader, BufWriter, Write},
-    path::Path,
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
 use bookmark::Bookmark;
+use chrono::Utc;
 use entry::Entry;
 use refs::{BookmarkMut, BookmarkRef};
+use sync::{Merged, Snapshot};
+use url::{Url, UrlPolicy};
 
 /// A store for bookmarks and other entries.
 pub struct Store {
     pub entries: Vec<Option<Entry>>,
     pub bookmark_index: Vec<usize>,
     pub tag_index: HashMap<String, Vec<usize>>, // maps tags to bookmark IDs
+    pub url_policy: UrlPolicy, // applied to URLs added, edited or imported, not to loaded ones
+    pub path: Option<PathBuf>, // the file last loaded or saved
+    pub normalize_tags: bool,  // whether tags are normalized as they are added
+    disk: Option<Snapshot>,    // what the file at `path` held when last loaded or saved
 }
 
 impl Store {
@@ -28,61 +42,187 @@ impl Store {
             entries: Vec::new(),
             bookmark_index: Vec::new(),
             tag_index: HashMap::new(),
+            url_policy: UrlPolicy::default(),
+            path: None,
+            normalize_tags: false,
+            disk: None,
         }
     }
 
     /// Load entries from a file    
+    ///
+    /// Bookmarks belong to the collection of the last section header before them. A new store
+    /// is then saved back to the file; a store that already has entries or a file of its own
+    /// keeps saving where it did, with the file it was opened from as the base of any merge.
     pub fn load(&mut self, path: impl AsRef<Path>) -> Result<()> {
-        let reader = BufReader::new(File::open(path)?);
-        reader
-            .lines()
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let new = self.entries.is_empty() && self.path.is_none();
+        self.read(&text)?;
+
+        if new {
+            self.disk = Some(Snapshot::new(path, text));
+            self.path = Some(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    /// Add the entries of a store file's text
+    ///
+    /// Invalid URLs are kept verbatim whatever the URL policy, so that one old line cannot keep
+    /// the whole file from loading.
+    fn read(&mut self, text: &str) -> Result<()> {
+        let mut section = None;
+        text.lines()
             .enumerate()
             .try_for_each(|(line_number, line)| {
-                let line = line.context("Failed to read line")?;
-                let entry = line.parse::<Entry>().with_context(|| {
+                let mut entry = Entry::parse(line, UrlPolicy::Warn).with_context(|| {
                     format!("Failed to parse entry on line {}", line_number + 1)
                 })?;
+                match &mut entry {
+                    Entry::Section(collection) => section.clone_from(collection),
+                    Entry::Bookmark(bookmark) => bookmark.collection.clone_from(&section),
+                    _ => {}
+                }
                 self.add_entry(entry);
 
                 Ok::<_, anyhow::Error>(())
-            })?;
-
-        Ok(())
+            })
     }
 
     /// Save entries to a file
-    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        let mut writer = BufWriter::new(File::create(path)?);
+    ///
+    /// If this is the file the store was loaded from and it has changed since, the changes made
+    /// to it are merged in first, and returned. Changes to the same bookmark on both sides are an
+    /// error, a [`MergeConflict`](sync::MergeConflict), and nothing is written. Paths are
+    /// compared as files, so `./store.inno` and `store.inno` are the same.
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<Option<Merged>> {
+        let path = path.as_ref();
+        let _lock = sync::lock(path)?;
+
+        let loaded_from = self
+            .path
+            .as_deref()
+            .is_some_and(|own| sync::same_file(own, path));
+        let changed = match &self.disk {
+            Some(disk) if loaded_from => disk.changed(path)?.map(|text| (disk.text.clone(), text)),
+            _ => None,
+        };
+        let merged = match changed {
+            Some((base_text, their_text)) => {
+                let mut base = Store::new();
+                let mut theirs = Store::new();
+                base.read(&base_text)?;
+                theirs.read(&their_text)?;
+                Some(self.merge(&base, &theirs)?)
+            }
+            None => None,
+        };
+
+        self.write(path)?;
+        Ok(merged)
+    }
+
+    /// Save entries to a file, overwriting any changes made to it since it was loaded
+    pub fn save_force(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let _lock = sync::lock(path)?;
+        self.write(path)
+    }
+
+    fn write(&mut self, path: &Path) -> Result<()> {
+        let text = self.render();
+        sync::write(path, &text)?;
+
+        self.disk = Some(Snapshot::new(path, text));
+        self.path = Some(path.to_path_buf());
+        Ok(())
+    }
 
+    /// Render entries as the text of a store file
+    ///
+    /// A section header is written before any bookmark that is not in the collection of the
+    /// section it would otherwise fall under, e.g. one that was added or filed elsewhere, and
+    /// headers that would repeat the current section are left out.
+    fn render(&self) -> String {
+        let mut text = String::new();
+        let mut section = None;
         for entry in self.entries.iter().flatten() {
-            writeln!(writer, "{}", entry)?;
+            match entry {
+                Entry::Section(collection) if collection.as_deref() == section => continue,
+                Entry::Section(collection) => section = collection.as_deref(),
+                Entry::Bookmark(bookmark) if bookmark.collection.as_deref() != section => {
+                    section = bookmark.collection.as_deref();
+                    text.push_str(&format!(
+                        "{}\n",
+                        Entry::Section(bookmark.collection.clone())
+                    ));
+                }
+                _ => {}
+            }
+            text.push_str(&format!("{}\n", entry));
         }
+        text
+    }
 
-        Ok(())
+    /// Save entries to the file last loaded or saved
+    pub fn save_to_path(&mut self) -> Result<Option<Merged>> {
+        let path = self
+            .path
+            .clone()
+            .context("The store has no file to save to")?;
+        self.save(path)
+    }
+
+    /// Get the directory that archived pages are kept in, next to the store file
+    pub fn archive_dir(&self) -> Option<PathBuf> {
+        self.path
+            .as_ref()
+            .map(|path| path.with_extension("archive"))
+    }
+
+    /// Get the path of an archived page by its content hash
+    ///
+    /// Archived files are named after their hash, with an extension for their type.
+    pub fn archive_path(&self, hash: &str) -> Option<PathBuf> {
+        let dir = self.archive_dir()?;
+        let found = fs::read_dir(&dir).ok().and_then(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .find(|path| path.file_stem().is_some_and(|stem| stem == hash))
+        });
+        Some(found.unwrap_or_else(|| dir.join(format!("{}.html", hash))))
     }
 
     /// Add a bookmark to the store and return its bookmark ID
-    pub fn add(&mut self, bookmark: Bookmark) -> usize {
-        self.add_entry(Entry::Bookmark(bookmark))
+    ///
+    /// Bookmarks without a creation time are stamped with the current time.
+    pub fn add(&mut self, mut bookmark: Bookmark) -> usize {
+        bookmark.created.get_or_insert_with(Utc::now);
+        self.add_entry(Entry::Bookmark(Box::new(bookmark)))
     }
 
     /// Create a new bookmark and add it to the store
-    pub fn create(&mut self, name: String, url: String, tags: Vec<String>) -> usize {
-        self.add(Bookmark { name, url, tags })
+    pub fn create(&mut self, name: String, url: Url, tags: Vec<String>) -> usize {
+        self.add(Bookmark::new(name, url, tags))
     }
 
     /// Iterate over all bookmarks in the store
-    pub fn iter(&self) -> impl Iterator<Item = BookmarkRef> + '_ {
-        self.bookmark_index.iter().filter_map(move |&id| {
-            self.entries[id]
-                .as_ref()?
-                .bookmark()
-                .map(|bookmark| BookmarkRef::new(id, bookmark))
-        })
+    pub fn iter(&self) -> impl Iterator<Item = BookmarkRef<'_>> + '_ {
+        self.bookmark_index
+            .iter()
+            .enumerate()
+            .filter_map(move |(id, &entry_id)| {
+                self.entries[entry_id]
+                    .as_ref()?
+                    .bookmark()
+                    .map(|bookmark| BookmarkRef::new(id, bookmark))
+            })
     }
 
     /// Get a reference to a bookmark by bookmark ID
-    pub fn get(&self, bookmark_id: usize) -> Option<BookmarkRef> {
+    pub fn get(&self, bookmark_id: usize) -> Option<BookmarkRef<'_>> {
         let entry_id = *self.bookmark_index.get(bookmark_id)?;
         self.entries
             .get(entry_id)?
@@ -92,7 +232,7 @@ impl Store {
     }
 
     /// Get a mutable reference to a bookmark by bookmark ID
-    pub fn get_mut(&mut self, bookmark_id: usize) -> Option<BookmarkMut> {
+    pub fn get_mut(&mut self, bookmark_id: usize) -> Option<BookmarkMut<'_>> {
         let entry_id = *self.bookmark_index.get(bookmark_id)?;
         self.entries
             .get_mut(entry_id)?
@@ -101,6 +241,17 @@ impl Store {
             .map(|bookmark| BookmarkMut::new(bookmark_id, bookmark))
     }
 
+    /// Record that a bookmark was opened, returning `false` if it does not exist
+    pub fn visit(&mut self, bookmark_id: usize) -> bool {
+        let Some(mut bookmark) = self.get_mut(bookmark_id) else {
+            return false;
+        };
+        let bookmark = bookmark.untracked();
+        bookmark.visits += 1;
+        bookmark.visited = Some(Utc::now());
+        true
+    }
+
     /// Get the number of bookmarks in the store
     pub fn bookmark_count(&self) -> usize {
         self.bookmark_index.len()
@@ -117,20 +268,195 @@ impl Store {
     }
 
     /// Get all bookmarks that match a given query
-    pub fn find(&self, query: &str) -> Vec<BookmarkRef> {
+    pub fn find(&self, query: &str) -> Vec<BookmarkRef<'_>> {
         self.iter()
             .filter(|bookmark| bookmark.matches(query))
             .collect()
     }
 
     /// Get all bookmarks that match a given tag
-    pub fn find_by_tag(&self, tag: &str) -> Vec<BookmarkRef> {
+    pub fn find_by_tag(&self, tag: &str) -> Vec<BookmarkRef<'_>> {
         self.tag_index
             .get(tag)
             .map(|bookmark_ids| bookmark_ids.iter().filter_map(|&id| self.get(id)).collect())
             .unwrap_or_default()
     }
 
+    /// Get the first bookmark whose URL normalizes to the same form as the given URL
+    pub fn find_url(&self, url: &str) -> Option<BookmarkRef<'_>> {
+        let url = normalize::normalize(url);
+        self.iter()
+            .find(|bookmark| normalize::normalize(bookmark.url.as_str()) == url)
+    }
+
+    /// Get every collection, from section headers and bookmarks, including their ancestors
+    ///
+    /// Collections are sorted by path, so each comes right after its parent.
+    pub fn collections(&self) -> Vec<String> {
+        let sections = self
+            .entries
+            .iter()
+            .flatten()
+            .filter_map(|entry| match entry {
+                Entry::Section(collection) => collection.as_deref(),
+                _ => None,
+            });
+        let bookmarks = self
+            .iter()
+            .filter_map(|bookmark| bookmark.bookmark.collection.as_deref());
+
+        let mut collections = BTreeSet::new();
+        for mut collection in sections.chain(bookmarks) {
+            collections.insert(collection.to_string());
+            while let Some(parent) = collection::parent(collection) {
+                collections.insert(parent.to_string());
+                collection = parent;
+            }
+        }
+        collections.into_iter().collect()
+    }
+
+    /// Add an empty collection, written as a section header, unless it already exists
+    pub fn add_collection(&mut self, path: &str) -> bool {
+        if self
+            .collections()
+            .iter()
+            .any(|collection| collection == path)
+        {
+            return false;
+        }
+        self.add_entry(Entry::Section(Some(path.to_string())));
+        true
+    }
+
+    /// File a bookmark under a collection, or at the top level if `None`
+    pub fn set_collection(&mut self, bookmark_id: usize, collection: Option<String>) -> bool {
+        match self.get_mut(bookmark_id) {
+            Some(mut bookmark) if bookmark.collection != collection => {
+                bookmark.collection = collection;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get groups of bookmark IDs whose URLs normalize to the same form
+    pub fn duplicates(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut by_url: HashMap<String, usize> = HashMap::new();
+
+        for bookmark in self.iter() {
+            let url = normalize::normalize(bookmark.url.as_str());
+            match by_url.get(&url) {
+                Some(&group) => groups[group].1.push(bookmark.id),
+                None => {
+                    by_url.insert(url.clone(), groups.len());
+                    groups.push((url, vec![bookmark.id]));
+                }
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(_, ids)| ids)
+            .filter(|ids| ids.len() > 1)
+            .collect()
+    }
+
+    /// Merge each group of duplicate bookmarks into the first bookmark of the group
+    ///
+    /// The kept bookmark gains the union of the group's tags and its earliest creation time.
+    /// Returns the number of bookmarks removed.
+    pub fn dedupe(&mut self) -> usize {
+        let mut removed = Vec::new();
+
+        for group in self.duplicates() {
+            let (&keep, rest) = group.split_first().expect("groups are never empty");
+            let mut tags = self.get(keep).map(|b| b.tags.clone()).unwrap_or_default();
+            let mut created = self.get(keep).and_then(|b| b.created);
+
+            for &id in rest {
+                let Some(duplicate) = self.get(id) else {
+                    continue;
+                };
+                for tag in &duplicate.tags {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+                created = match (created, duplicate.created) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+
+            self.set_tags(keep, tags);
+            if let Some(mut bookmark) = self.get_mut(keep) {
+                bookmark.created = created;
+            }
+            removed.extend_from_slice(rest);
+        }
+
+        // Remove from the back so earlier IDs stay valid
+        removed.sort_unstable();
+        for &id in removed.iter().rev() {
+            self.remove(id);
+        }
+
+        removed.len()
+    }
+
+    /// Replace a bookmark's tags, keeping the tag index up to date
+    pub fn set_tags(&mut self, bookmark_id: usize, tags: Vec<String>) -> bool {
+        let tags = self.clean_tags(tags);
+        let Some(old_tags) = self.get(bookmark_id).map(|b| b.tags.clone()) else {
+            return false;
+        };
+
+        self.remove_from_tag_index(bookmark_id, &old_tags);
+        self.index_tags(bookmark_id, &tags);
+        if let Some(mut bookmark) = self.get_mut(bookmark_id) {
+            bookmark.tags = tags;
+        }
+
+        true
+    }
+
+    /// Replace a bookmark, keeping the tag index up to date and returning the old bookmark
+    pub fn replace(&mut self, bookmark_id: usize, bookmark: Bookmark) -> Option<Bookmark> {
+        self.replace_with(bookmark_id, bookmark, true)
+    }
+
+    /// Replace a bookmark like [`Store::replace`], but keep the new bookmark's modification time
+    /// rather than marking it as modified now
+    pub fn replace_untracked(
+        &mut self,
+        bookmark_id: usize,
+        bookmark: Bookmark,
+    ) -> Option<Bookmark> {
+        self.replace_with(bookmark_id, bookmark, false)
+    }
+
+    fn replace_with(
+        &mut self,
+        bookmark_id: usize,
+        mut bookmark: Bookmark,
+        tracked: bool,
+    ) -> Option<Bookmark> {
+        bookmark.tags = self.clean_tags(std::mem::take(&mut bookmark.tags));
+        let old_tags = self.get(bookmark_id)?.tags.clone();
+        self.remove_from_tag_index(bookmark_id, &old_tags);
+        self.index_tags(bookmark_id, &bookmark.tags);
+
+        let mut existing = self.get_mut(bookmark_id)?;
+        let existing = if tracked {
+            &mut *existing
+        } else {
+            existing.untracked()
+        };
+        Some(std::mem::replace(existing, bookmark))
+    }
+
     /// Remove a bookmark by bookmark ID
     pub fn remove(&mut self, bookmark_id: usize) -> Option<Bookmark> {
         if bookmark_id >= self.bookmark_index.len() {
@@ -145,14 +471,23 @@ impl Store {
         }
 
         self.bookmark_index.remove(bookmark_id);
+
+        // Bookmark IDs after the removed one shift down by one
+        for ids in self.tag_index.values_mut() {
+            for id in ids.iter_mut().filter(|id| **id > bookmark_id) {
+                *id -= 1;
+            }
+        }
+
         removed_entry.into_bookmark()
     }
 
     /// Add an entry to the store, returning the entry ID (bookmark ID if a bookmark)
-    fn add_entry(&mut self, entry: Entry) -> usize {
+    fn add_entry(&mut self, mut entry: Entry) -> usize {
         let mut id = self.entries.len();
 
-        if let Entry::Bookmark(bookmark) = &entry {
+        if let Entry::Bookmark(bookmark) = &mut entry {
+            bookmark.tags = self.clean_tags(std::mem::take(&mut bookmark.tags));
             self.bookmark_index.push(id);
             let bookmark_id = self.bookmark_index.len() - 1;
             self.index_tags(bookmark_id, &bookmark.tags);
@@ -164,6 +499,20 @@ impl Store {
         id
     }
 
+    /// Normalizes tags if the store is set to, dropping tags that become duplicates
+    fn clean_tags(&self, tags: Vec<String>) -> Vec<String> {
+        if !self.normalize_tags {
+            return tags;
+        }
+        let mut cleaned: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags.iter().map(|tag| normalize::tag(tag)) {
+            if !tag.is_empty() && !cleaned.contains(&tag) {
+                cleaned.push(tag);
+            }
+        }
+        cleaned
+    }
+
     fn index_tags(&mut self, bookmark_id: usize, tags: &[String]) {
         for tag in tags {
             self.tag_index
@@ -203,16 +552,18 @@ mod tests {
         // Populate the store with some bookmarks & a comment
         store.add(Bookmark {
             name: "Rust".to_string(),
-            url: "https://www.rust-lang.org".to_string(),
+            url: "https://www.rust-lang.org".into(),
             tags: vec!["rust".to_string()],
+            ..Default::default()
         }); // bookmark_id = 0, entry_id = 0
 
         store.add_entry(Entry::Comment("A comment".to_string())); // entry_id = 1, not a bookmark
 
         store.add(Bookmark {
             name: "The Rust Programming Language".to_string(),
-            url: "https://doc.rust-lang.org/book/".to_string(),
+            url: "https://doc.rust-lang.org/book/".into(),
             tags: vec!["rust".to_string()],
+            ..Default::default()
         }); // bookmark_id = 1, entry_id = 2
 
         store
@@ -247,6 +598,44 @@ mod tests {
         assert_eq!(bookmark.name, "Rust Programming");
     }
 
+    #[test]
+    fn test_timestamps() {
+        let mut store = mock_store();
+        assert!(store.get(0).unwrap().created.is_some());
+        assert!(store.get(0).unwrap().modified.is_none());
+
+        // Visits and link checks are not modifications
+        store.visit(0);
+        store.get_mut(0).unwrap().untracked().checked = Some(Utc::now());
+        let bookmark = store.get(0).unwrap();
+        assert_eq!(bookmark.visits, 1);
+        assert!(bookmark.visited.is_some());
+        assert!(bookmark.modified.is_none());
+
+        store.set_tags(0, vec!["lang".to_string()]);
+        assert!(store.get(0).unwrap().modified.is_some());
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let mut store = Store::new();
+        store.normalize_tags = true;
+        store.create(
+            "Rust".to_string(),
+            "https://www.rust-lang.org".into(),
+            vec![
+                "Rust".to_string(),
+                "rust".to_string(),
+                "Systems  Lang".to_string(),
+            ],
+        );
+        assert_eq!(store.get(0).unwrap().tags, vec!["rust", "systems-lang"]);
+        assert_eq!(store.find_by_tag("systems-lang").len(), 1);
+
+        store.set_tags(0, vec!["To Read".to_string()]);
+        assert_eq!(store.get(0).unwrap().tags, vec!["to-read"]);
+    }
+
     #[test]
     fn test_remove() {
         let mut store = mock_store();
@@ -264,6 +653,95 @@ mod tests {
         assert!(store.get(1).is_none());
     }
 
+    #[test]
+    fn test_remove_updates_tag_index() {
+        let mut store = mock_store();
+
+        store.remove(0);
+
+        let bookmarks = store.find_by_tag("rust");
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].name, "The Rust Programming Language");
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let mut store = mock_store();
+        store.add(Bookmark {
+            name: "Rust (again)".to_string(),
+            url: "http://WWW.rust-lang.org/?utm_source=feed".into(),
+            tags: vec!["lang".to_string()],
+            ..Default::default()
+        }); // bookmark_id = 2
+
+        assert_eq!(store.duplicates(), vec![vec![0, 2]]);
+        assert_eq!(store.dedupe(), 1);
+        assert!(store.duplicates().is_empty());
+
+        let bookmark = store.get(0).unwrap();
+        assert_eq!(bookmark.name, "Rust");
+        assert_eq!(bookmark.tags, vec!["rust", "lang"]);
+        assert_eq!(store.find_by_tag("lang").len(), 1);
+        assert_eq!(store.bookmark_count(), 2);
+    }
+
+    #[test]
+    fn test_find_matches_aliases() {
+        let mut store = mock_store();
+
+        store
+            .get_mut(1)
+            .unwrap()
+            .rewrite_url("https://doc.rust-lang.org/stable/book/".into());
+
+        let bookmark = store.get(1).unwrap();
+        assert_eq!(bookmark.aliases.len(), 1);
+        assert_eq!(store.find("/book/").len(), 1);
+        assert_eq!(store.find("doc.rust-lang.org/book").len(), 1);
+    }
+
+    #[test]
+    fn test_collections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.inno");
+        std::fs::write(
+            &path,
+            "a: https://a.example []\n[Work/Rust]\nb: https://b.example []\n[Home]\n[/]\n",
+        )
+        .unwrap();
+
+        let mut store = Store::new();
+        store.load(&path).unwrap();
+        let collections: Vec<_> = store.iter().map(|b| b.collection.clone()).collect();
+        assert_eq!(collections, vec![None, Some("Work/Rust".to_string())]);
+        assert_eq!(store.collections(), vec!["Home", "Work", "Work/Rust"]);
+
+        // Bookmarks filed elsewhere than the section they fall under get a header of their own
+        store.set_collection(0, Some("Home".to_string()));
+        store.create("c".to_string(), "https://c.example".into(), vec![]);
+        assert!(store.add_collection("Music"));
+        assert!(!store.add_collection("Work"));
+        store.save(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = text
+            .lines()
+            .map(|line| line.split(" {").next().unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[Home]",
+                "a: https://a.example/ []",
+                "[Work/Rust]",
+                "b: https://b.example/ []",
+                "[Home]",
+                "[/]",
+                "c: https://c.example/ []",
+                "[Music]",
+            ]
+        );
+    }
+
     #[test]
     fn test_tombstone_preservation() {
         let mut store = mock_store();
@@ -275,4 +753,16 @@ mod tests {
         assert!(store.entries[1].is_some()); // Comment remains in place
         assert!(store.entries[0].is_none()); // Tombstoned entry
     }
+
+    #[test]
+    fn test_read_keeps_invalid_urls() {
+        let mut store = Store::new();
+        store.url_policy = UrlPolicy::Reject;
+        store
+            .read("foo: bar [x]\nRust: https://www.rust-lang.org []\n")
+            .unwrap();
+
+        assert_eq!(store.bookmark_count(), 2);
+        assert!(!store.get(0).unwrap().url.is_valid());
+    }
 }
@@ -0,0 +1,160 @@
+//! URL and tag normalization, used to recognise different spellings of the same thing.
+
+use url::Url;
+
+/// Returns the canonical form of a tag: lowercase, with runs of whitespace replaced by '-'.
+pub fn tag(tag: &str) -> String {
+    tag.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// Query parameters that only track where a click came from.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid", "igshid", "_hsenc",
+    "_hsmi", "ref_src", "si",
+];
+
+/// Prefixes of query parameters that only track where a click came from.
+const TRACKING_PREFIXES: &[&str] = &["utm_", "pk_", "mtm_"];
+
+/// What to do with the `#fragment` of a URL.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FragmentPolicy {
+    /// Fragments only select a position within a page, so drop them.
+    #[default]
+    Strip,
+    /// Fragments are significant (e.g. single-page applications), so keep them.
+    Keep,
+}
+
+/// Rules for turning a URL into a canonical form for comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Normalizer {
+    /// Whether to remove tracking parameters such as `utm_source` from the query.
+    pub strip_tracking: bool,
+    /// Whether `http:` and `https:` URLs to the same place are considered equal.
+    pub unify_scheme: bool,
+    /// What to do with the fragment.
+    pub fragment: FragmentPolicy,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self {
+            strip_tracking: true,
+            unify_scheme: true,
+            fragment: FragmentPolicy::default(),
+        }
+    }
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    TRACKING_PARAMS.contains(&name.as_str())
+        || TRACKING_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+impl Normalizer {
+    /// Returns the canonical form of a URL.
+    ///
+    /// Scheme and host case and default ports are normalized by parsing; the rules of this
+    /// normalizer are then applied, and a trailing slash on the path is removed. Strings
+    /// that are not absolute URLs are only trimmed and lowercased.
+    pub fn normalize(&self, url: &str) -> String {
+        let url = url.trim();
+        let Ok(mut parsed) = Url::parse(url) else {
+            return url.to_lowercase();
+        };
+
+        if self.unify_scheme && parsed.scheme() == "http" {
+            // Both schemes are "special" to the URL parser, so this cannot fail
+            let _ = parsed.set_scheme("https");
+            if parsed.port() == Some(443) {
+                let _ = parsed.set_port(None);
+            }
+        }
+
+        if self.fragment == FragmentPolicy::Strip {
+            parsed.set_fragment(None);
+        }
+
+        if self.strip_tracking && parsed.query().is_some() {
+            let kept: Vec<(String, String)> = parsed
+                .query_pairs()
+                .filter(|(name, _)| !is_tracking_param(name))
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect();
+            if kept.is_empty() {
+                parsed.set_query(None);
+            } else {
+                parsed.query_pairs_mut().clear().extend_pairs(kept);
+            }
+        }
+
+        let path = parsed.path().trim_end_matches('/').to_string();
+        if !parsed.cannot_be_a_base() {
+            parsed.set_path(&path);
+        }
+
+        let mut normalized = parsed.to_string();
+        // An empty path is still serialized as "/" when there is nothing after it
+        if parsed.query().is_none() && parsed.fragment().is_none() && path.is_empty() {
+            normalized.truncate(normalized.trim_end_matches('/').len());
+        }
+        normalized
+    }
+}
+
+/// Returns the canonical form of a URL using the default rules.
+pub fn normalize(url: &str) -> String {
+    Normalizer::default().normalize(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_urls() {
+        let urls = [
+            "http://example.com/",
+            "https://example.com",
+            "https://EXAMPLE.com/?utm_source=x",
+            "https://example.com:443/#section",
+            "HTTP://example.com:80",
+        ];
+        for url in urls {
+            assert_eq!(normalize(url), "https://example.com", "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_significant_parts_are_kept() {
+        assert_eq!(
+            normalize("https://example.com/a/b/?q=1&utm_medium=email&fbclid=2"),
+            "https://example.com/a/b?q=1"
+        );
+        assert_eq!(
+            normalize("https://example.com:8080/Path"),
+            "https://example.com:8080/Path"
+        );
+        assert_eq!(
+            normalize("mailto:Someone@Example.com"),
+            "mailto:Someone@Example.com"
+        );
+
+        let keep = Normalizer {
+            fragment: FragmentPolicy::Keep,
+            unify_scheme: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            keep.normalize("http://example.com/#top"),
+            "http://example.com/#top"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Queries over bookmarks: words to match, field filters and a sort order.
+//!
+//! A query is a list of terms, e.g. `rust tag:lang added:>2026-01-01 sort:-added`:
+//!
+//! * `tag:<tag>` matches bookmarks with that tag.
+//! * `in:<collection>` matches bookmarks in that collection or below it.
+//! * `added:`, `modified:` and `visited:` compare a timestamp's date with `<`, `<=`, `>`, `>=`
+//!   or (without an operator) equality. Dates are `YYYY-MM-DD`, `today`, `yesterday`, or a
+//!   number of days or weeks ago such as `7d` or `2w`.
+//! * `sort:<order>` sorts the results; a leading `-` reverses the order.
+//! * Anything else must appear in the bookmark's name, URL or tags.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use thiserror::Error;
+
+use super::{
+    Store,
+    bookmark::Bookmark,
+    collection::{self, CollectionError},
+    refs::BookmarkRef,
+};
+
+/// The order in which bookmarks are listed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Keep the order the bookmarks have in the store.
+    #[default]
+    Store,
+    Name,
+    Url,
+    /// Oldest first; bookmarks without the timestamp come last.
+    Added,
+    Modified,
+    Visited,
+    /// Most visited first.
+    Visits,
+    /// By first tag; untagged bookmarks come last.
+    Tag,
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown sort order: '{0}'")]
+pub struct UnknownSortOrder(String);
+
+impl FromStr for SortOrder {
+    type Err = UnknownSortOrder;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "store" => Ok(SortOrder::Store),
+            "name" => Ok(SortOrder::Name),
+            "url" => Ok(SortOrder::Url),
+            "added" => Ok(SortOrder::Added),
+            "modified" => Ok(SortOrder::Modified),
+            "visited" => Ok(SortOrder::Visited),
+            "visits" => Ok(SortOrder::Visits),
+            "tag" => Ok(SortOrder::Tag),
+            _ => Err(UnknownSortOrder(s.to_string())),
+        }
+    }
+}
+
+impl SortOrder {
+    /// Sorts bookmarks in place; the sort is stable.
+    pub fn sort(self, bookmarks: &mut [BookmarkRef]) {
+        match self {
+            SortOrder::Store => {}
+            SortOrder::Name => bookmarks.sort_by_key(|b| b.name.to_lowercase()),
+            SortOrder::Url => bookmarks.sort_by(|a, b| a.url.as_str().cmp(b.url.as_str())),
+            SortOrder::Added => sort_by_time(bookmarks, Bookmark::created),
+            SortOrder::Modified => sort_by_time(bookmarks, Bookmark::modified),
+            SortOrder::Visited => sort_by_time(bookmarks, Bookmark::visited),
+            SortOrder::Visits => bookmarks.sort_by_key(|b| std::cmp::Reverse(b.visits)),
+            SortOrder::Tag => bookmarks.sort_by_key(|b| {
+                let tag = b.tags.first().map(|tag| tag.to_lowercase());
+                (tag.is_none(), tag)
+            }),
+        }
+    }
+}
+
+fn sort_by_time(bookmarks: &mut [BookmarkRef], time: fn(&Bookmark) -> Option<DateTime<Utc>>) {
+    bookmarks.sort_by_key(|b| (time(b).is_none(), time(b)));
+}
+
+#[derive(Error, Debug)]
+pub enum QueryError {
+    #[error("Invalid date in '{0}' (expected e.g. 2026-01-31, today or 7d)")]
+    InvalidDate(String),
+    #[error(transparent)]
+    UnknownSortOrder(#[from] UnknownSortOrder),
+    #[error(transparent)]
+    InvalidCollection(#[from] CollectionError),
+}
+
+/// A timestamp of a bookmark that can be filtered on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DateField {
+    Added,
+    Modified,
+    Visited,
+}
+
+/// A comparison against a bookmark's timestamp, at the granularity of days.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DateFilter {
+    field: DateField,
+    ordering: std::cmp::Ordering,
+    or_equal: bool,
+    date: NaiveDate,
+}
+
+impl DateFilter {
+    fn parse(field: DateField, term: &str, value: &str) -> Result<Self, QueryError> {
+        use std::cmp::Ordering::*;
+
+        let (ordering, or_equal, date) = if let Some(date) = value.strip_prefix(">=") {
+            (Greater, true, date)
+        } else if let Some(date) = value.strip_prefix("<=") {
+            (Less, true, date)
+        } else if let Some(date) = value.strip_prefix('>') {
+            (Greater, false, date)
+        } else if let Some(date) = value.strip_prefix('<') {
+            (Less, false, date)
+        } else {
+            (Equal, true, value)
+        };
+
+        let date = parse_date(date, Utc::now().date_naive())
+            .ok_or_else(|| QueryError::InvalidDate(term.to_string()))?;
+
+        Ok(Self {
+            field,
+            ordering,
+            or_equal,
+            date,
+        })
+    }
+
+    fn matches(&self, bookmark: &Bookmark) -> bool {
+        let time = match self.field {
+            DateField::Added => bookmark.created,
+            DateField::Modified => bookmark.modified,
+            DateField::Visited => bookmark.visited,
+        };
+        let Some(time) = time else {
+            return false;
+        };
+
+        let ordering = time.date_naive().cmp(&self.date);
+        ordering == self.ordering || (self.or_equal && ordering.is_eq())
+    }
+}
+
+/// Parses an absolute or relative date, relative to `today`.
+fn parse_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let days_ago = |days: u64| today.checked_sub_days(Days::new(days));
+
+    match text {
+        "today" => Some(today),
+        "yesterday" => days_ago(1),
+        _ => {
+            if let Some(days) = text.strip_suffix('d') {
+                return days_ago(days.parse().ok()?);
+            }
+            if let Some(weeks) = text.strip_suffix('w') {
+                return days_ago(weeks.parse::<u64>().ok()?.checked_mul(7)?);
+            }
+            NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+        }
+    }
+}
+
+/// A parsed query; see the [module documentation](self) for the syntax.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    words: Vec<String>,
+    tags: Vec<String>,
+    dates: Vec<DateFilter>,
+    /// The collection that bookmarks must be in, directly or below it.
+    pub collection: Option<String>,
+    /// The order of the results.
+    pub sort: SortOrder,
+    /// Whether to reverse the order of the results.
+    pub reverse: bool,
+}
+
+impl Query {
+    /// Parses a query from its terms.
+    pub fn parse<S: AsRef<str>>(terms: &[S]) -> Result<Self, QueryError> {
+        let mut query = Query::default();
+
+        for term in terms.iter().map(AsRef::as_ref) {
+            let (field, value) = term.split_once(':').unwrap_or(("", term));
+            match field {
+                "tag" => query.tags.push(value.to_string()),
+                "in" => query.collection = collection::resolve(None, value)?,
+                "added" => query
+                    .dates
+                    .push(DateFilter::parse(DateField::Added, term, value)?),
+                "modified" => {
+                    query
+                        .dates
+                        .push(DateFilter::parse(DateField::Modified, term, value)?)
+                }
+                "visited" => query
+                    .dates
+                    .push(DateFilter::parse(DateField::Visited, term, value)?),
+                "sort" => match value.strip_prefix('-') {
+                    Some(order) => {
+                        query.sort = order.parse()?;
+                        query.reverse = true;
+                    }
+                    None => {
+                        query.sort = value.parse()?;
+                        query.reverse = false;
+                    }
+                },
+                _ => query.words.push(term.to_string()),
+            }
+        }
+
+        Ok(query)
+    }
+
+    /// Returns `true` if a bookmark matches every term of the query.
+    pub fn matches(&self, bookmark: &Bookmark) -> bool {
+        self.words.iter().all(|word| bookmark.matches(word))
+            && self.tags.iter().all(|tag| bookmark.tags.contains(tag))
+            && self.dates.iter().all(|filter| filter.matches(bookmark))
+            && collection::within(bookmark.collection.as_deref(), self.collection.as_deref())
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    /// Parses a query, splitting terms at whitespace outside of quotes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms: Vec<String> = shlex::Shlex::new(s).collect();
+        Self::parse(&terms)
+    }
+}
+
+impl Store {
+    /// Get all bookmarks that match a query, in the query's order
+    pub fn query(&self, query: &Query) -> Vec<BookmarkRef<'_>> {
+        let mut bookmarks: Vec<_> = self
+            .iter()
+            .filter(|bookmark| query.matches(bookmark))
+            .collect();
+        query.sort.sort(&mut bookmarks);
+        if query.reverse {
+            bookmarks.reverse();
+        }
+        bookmarks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(name: &str, tags: &[&str], created: &str) -> Bookmark {
+        let mut bookmark = Bookmark::new(
+            name.to_string(),
+            format!("https://example.com/{}", name).into(),
+            tags.iter().map(ToString::to_string).collect(),
+        );
+        bookmark.set_attr("created", created).unwrap();
+        bookmark
+    }
+
+    #[test]
+    fn test_query() {
+        let mut store = Store::new();
+        store.add(bookmark("rust", &["lang"], "2026-01-10T12:00:00Z"));
+        store.add(bookmark("go", &["lang"], "2025-12-31T23:00:00Z"));
+        store.add(bookmark("rust-blog", &["blog"], "2026-02-01T08:00:00Z"));
+
+        let names = |query: &str| -> Vec<String> {
+            let query: Query = query.parse().unwrap();
+            store.query(&query).iter().map(|b| b.name.clone()).collect()
+        };
+
+        assert_eq!(names("rust"), vec!["rust", "rust-blog"]);
+        assert_eq!(names("rust tag:lang"), vec!["rust"]);
+        assert_eq!(names("added:>=2026-01-10"), vec!["rust", "rust-blog"]);
+        assert_eq!(names("added:<2026-01-10 tag:lang"), vec!["go"]);
+        assert_eq!(names("added:2025-12-31"), vec!["go"]);
+        assert_eq!(names("sort:added"), vec!["go", "rust", "rust-blog"]);
+        assert_eq!(names("sort:-added"), vec!["rust-blog", "rust", "go"]);
+        assert_eq!(names("modified:today"), Vec::<String>::new());
+
+        assert!("added:>soon".parse::<Query>().is_err());
+        assert!("sort:size".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        assert_eq!(
+            parse_date("yesterday", today),
+            NaiveDate::from_ymd_opt(2026, 3, 14)
+        );
+        assert_eq!(parse_date("2w", today), NaiveDate::from_ymd_opt(2026, 3, 1));
+        assert_eq!(parse_date("xd", today), None);
+    }
+}
@@ -3,8 +3,11 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use chrono::Utc;
+
 use super::Bookmark;
 
+#[derive(Clone, Copy)]
 pub struct BookmarkRef<'a> {
     pub id: usize,
     pub bookmark: &'a Bookmark,
@@ -30,14 +33,27 @@ impl Display for BookmarkRef<'_> {
     }
 }
 
+/// A mutable reference to a bookmark that updates its modification time when dropped, if the
+/// bookmark was changed through it.
 pub struct BookmarkMut<'a> {
     pub id: usize,
     pub bookmark: &'a mut Bookmark,
+    modified: bool,
 }
 
 impl<'a> BookmarkMut<'a> {
     pub fn new(id: usize, bookmark: &'a mut Bookmark) -> Self {
-        Self { id, bookmark }
+        Self {
+            id,
+            bookmark,
+            modified: false,
+        }
+    }
+
+    /// Returns the bookmark for bookkeeping changes (e.g. link checks) that should not count as
+    /// modifying it.
+    pub fn untracked(&mut self) -> &mut Bookmark {
+        self.bookmark
     }
 }
 
@@ -51,10 +67,19 @@ impl Deref for BookmarkMut<'_> {
 
 impl DerefMut for BookmarkMut<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.modified = true;
         self.bookmark
     }
 }
 
+impl Drop for BookmarkMut<'_> {
+    fn drop(&mut self) {
+        if self.modified {
+            self.bookmark.modified = Some(Utc::now());
+        }
+    }
+}
+
 impl Display for BookmarkMut<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}. {}", self.id, self.bookmark)
@@ -0,0 +1,606 @@
+//! Keeping a store file intact when more than one inno uses it.
+//!
+//! Saving takes an advisory lock next to the file, and checks whether the file changed since it
+//! was last loaded or saved. If it did, the changes made there and the changes made here are
+//! merged, bookmark by bookmark, with the file as it was loaded as their common base.
+//!
+//! The lock is only held for the length of a save, so that two saves cannot interleave. It does
+//! not keep another inno from loading the same file and changing it meanwhile; that is what the
+//! merge is for.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::{Store, bookmark::Bookmark, collection, entry::Entry, normalize};
+
+/// What a store file held when it was last loaded or saved.
+pub struct Snapshot {
+    modified: Option<(SystemTime, u64)>,
+    hash: Vec<u8>,
+    pub text: String,
+}
+
+impl Snapshot {
+    /// Records the text just read from or written to a file.
+    pub fn new(path: &Path, text: String) -> Self {
+        Self {
+            modified: modified(path),
+            hash: Sha256::digest(&text).to_vec(),
+            text,
+        }
+    }
+
+    /// Returns the file's text if it has changed since the snapshot, or `None` if not.
+    ///
+    /// The modification time and size are checked first, so that the file is only read if it
+    /// was touched.
+    pub fn changed(&self, path: &Path) -> Result<Option<String>> {
+        if modified(path) == self.modified {
+            return Ok(None);
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            // A file removed since is written anew
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok((Sha256::digest(&text).as_slice() != self.hash).then_some(text))
+    }
+}
+
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Takes an exclusive advisory lock for a store file, held until the returned file is dropped.
+///
+/// The lock is on a separate `.lock` file, since the store file itself is replaced on saving. It
+/// sits beside the file a symlink points to, so that every path to the store takes one lock.
+pub fn lock(path: &Path) -> Result<File> {
+    let lock_path = sibling(&resolve(path), "lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(file)
+}
+
+/// Returns `true` if two paths name the same file, however they are spelled.
+pub fn same_file(a: &Path, b: &Path) -> bool {
+    resolve(a) == resolve(b)
+}
+
+/// Resolves symlinks and relative parts of a path, including one to a file not yet created.
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(path) {
+        return path;
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(parent), path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Writes a file by writing a temporary file beside it and moving that into place, so that
+/// readers never see it half-written.
+///
+/// A symlink is written through, keeping the link, and the file keeps its permissions.
+pub fn write(path: &Path, text: &str) -> Result<()> {
+    let path = resolve(path);
+    let temp_path = sibling(&path, "tmp");
+    let mut file = File::create(&temp_path)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    if let Ok(meta) = fs::metadata(&path) {
+        file.set_permissions(meta.permissions())?;
+    }
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp_path, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Returns a path beside `path` with an extra extension, e.g. `store.inno.lock`.
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Changes made both in the store file and here, differently, so that neither can be kept.
+#[derive(Error, Debug)]
+pub struct MergeConflict {
+    /// The URLs of bookmarks changed on both sides.
+    pub urls: Vec<String>,
+    /// Whether comments or blank lines were changed on both sides.
+    pub prose: bool,
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "The store file was changed elsewhere, and these were changed both there and here:"
+        )?;
+        for url in &self.urls {
+            writeln!(f, "  {}", url)?;
+        }
+        if self.prose {
+            writeln!(f, "  comments or blank lines")?;
+        }
+        write!(
+            f,
+            "Use 'save --force' to overwrite the file with the store as it is here"
+        )
+    }
+}
+
+/// The changes from the store file that a merge brought in.
+#[derive(Debug, Default, PartialEq)]
+pub struct Merged {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// Comments and blank lines added or removed, and empty collections added.
+    pub other: usize,
+}
+
+impl Display for Merged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Merged changes made to the file elsewhere: {} added, {} updated and {} removed",
+            self.added, self.updated, self.removed
+        )?;
+        if self.other > 0 {
+            write!(
+                f,
+                ", with {} comments, blank lines or collections",
+                self.other
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Bookmarks by normalized URL, counting repeats so that duplicates are kept apart.
+type Keyed<'a> = HashMap<(String, usize), (usize, String, &'a Bookmark)>;
+
+fn keyed(store: &Store) -> Keyed<'_> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    store
+        .iter()
+        .map(|bookmark| {
+            let url = normalize::normalize(bookmark.url.as_str());
+            let count = seen.entry(url.clone()).or_default();
+            *count += 1;
+
+            // Visits are merged by adding them up, so opening a bookmark is not a change here
+            let mut unvisited = bookmark.bookmark.clone();
+            unvisited.visits = 0;
+            unvisited.visited = None;
+            let line = format!(
+                "{}\t{}",
+                bookmark.collection.as_deref().unwrap_or_default(),
+                unvisited.line()
+            );
+            ((url, *count), (bookmark.id, line, bookmark.bookmark))
+        })
+        .collect()
+}
+
+/// How many times, and when last, each bookmark was opened.
+type Visits = HashMap<(String, usize), (u32, Option<DateTime<Utc>>)>;
+
+fn visits(side: &Keyed) -> Visits {
+    side.iter()
+        .map(|(key, (_, _, bookmark))| (key.clone(), (bookmark.visits, bookmark.visited)))
+        .collect()
+}
+
+/// Returns the keys of every bookmark on any side.
+fn keys<'a>(sides: [&'a Keyed; 3]) -> HashSet<&'a (String, usize)> {
+    sides.into_iter().flat_map(|side| side.keys()).collect()
+}
+
+fn line(side: &Keyed, key: &(String, usize)) -> Option<String> {
+    side.get(key).map(|(_, line, _)| line.clone())
+}
+
+/// Comments and blank lines, in order.
+///
+/// They have nothing to tell them apart by, so they are merged as a whole: where both sides
+/// changed them, differently, that is a conflict.
+fn prose(store: &Store) -> Vec<String> {
+    store
+        .entries
+        .iter()
+        .flatten()
+        .filter(|entry| matches!(entry, Entry::Comment(_) | Entry::Empty))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Collections named by section headers, including empty ones.
+fn sections(store: &Store) -> BTreeSet<String> {
+    store
+        .entries
+        .iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            Entry::Section(Some(collection)) => Some(collection.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Counts the lines in one list and not the other, either way round.
+fn difference(a: &[String], b: &[String]) -> usize {
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    for line in a {
+        *counts.entry(line).or_default() += 1;
+    }
+    for line in b {
+        *counts.entry(line).or_default() -= 1;
+    }
+    counts.values().map(|count| count.unsigned_abs()).sum()
+}
+
+impl Store {
+    /// Merges the changes made to the store file since `base` was loaded into this store.
+    ///
+    /// Where only one side changed a bookmark, that change is kept; where both did, differently,
+    /// nothing is merged and the bookmarks are reported as conflicts. Comments and blank lines
+    /// are kept from the side that changed them, and new empty collections from both sides.
+    /// Visits made on both sides are added up.
+    pub fn merge(&mut self, base: &Store, theirs: &Store) -> Result<Merged, MergeConflict> {
+        let (base_prose, their_prose, our_prose) = (prose(base), prose(theirs), prose(self));
+        let mut conflict = MergeConflict {
+            urls: Vec::new(),
+            prose: their_prose != base_prose && our_prose != base_prose && their_prose != our_prose,
+        };
+        let (base_keyed, their_keyed, our_keyed) = (keyed(base), keyed(theirs), keyed(self));
+        for key in keys([&base_keyed, &their_keyed, &our_keyed]) {
+            let (b, t, o) = (
+                line(&base_keyed, key),
+                line(&their_keyed, key),
+                line(&our_keyed, key),
+            );
+            if t != o && t != b && o != b {
+                let (_, _, bookmark) = our_keyed
+                    .get(key)
+                    .or(their_keyed.get(key))
+                    .expect("changed on a side");
+                conflict.urls.push(bookmark.url.as_str().to_string());
+            }
+        }
+        if !conflict.urls.is_empty() || conflict.prose {
+            conflict.urls.sort();
+            return Err(conflict);
+        }
+
+        let our_visits = visits(&our_keyed);
+        let before: HashMap<_, _> = our_keyed
+            .into_iter()
+            .map(|(key, (_, line, _))| (key, line))
+            .collect();
+        let collections_before: HashSet<_> = self.collections().into_iter().collect();
+        let base_sections = sections(base);
+        let new_sections: Vec<_> = sections(theirs)
+            .union(&sections(self))
+            .filter(|collection| !base_sections.contains(*collection))
+            .cloned()
+            .collect();
+
+        if their_prose != base_prose && our_prose == base_prose {
+            // Only the file's comments changed, so its layout is kept and our changes made to it
+            let ours = self.rebase(theirs);
+            self.take_changes(base, &ours);
+        } else {
+            self.take_changes(base, theirs);
+        }
+        for collection in &new_sections {
+            self.add_collection(collection);
+        }
+        self.take_visits(&visits(&base_keyed), &visits(&their_keyed), &our_visits);
+
+        let after: HashMap<_, _> = keyed(self)
+            .into_iter()
+            .map(|(key, (_, line, _))| (key, line))
+            .collect();
+        let new_collections = self
+            .collections()
+            .into_iter()
+            .filter(|c| !collections_before.contains(c))
+            .filter(|c| {
+                !self
+                    .iter()
+                    .any(|b| collection::within(b.collection.as_deref(), Some(c)))
+            })
+            .count();
+
+        Ok(Merged {
+            added: after.keys().filter(|key| !before.contains_key(key)).count(),
+            updated: after
+                .iter()
+                .filter(|(key, line)| before.get(key).is_some_and(|old| old != *line))
+                .count(),
+            removed: before.keys().filter(|key| !after.contains_key(key)).count(),
+            other: difference(&our_prose, &prose(self)) + new_collections,
+        })
+    }
+
+    /// Adds up the visits made here and in the file since `base`, keeping the latest visit.
+    fn take_visits(&mut self, base: &Visits, theirs: &Visits, ours: &Visits) {
+        let merged: Vec<_> = keyed(self)
+            .into_iter()
+            .map(|(key, (id, _, _))| {
+                let side = |visits: &Visits| visits.get(&key).copied().unwrap_or_default();
+                let ((base, _), (their, their_time), (our, our_time)) =
+                    (side(base), side(theirs), side(ours));
+                let count = our.saturating_add(their).saturating_sub(base);
+                (id, count, our_time.max(their_time))
+            })
+            .collect();
+
+        for (id, count, time) in merged {
+            let mut bookmark = self.get_mut(id).expect("merged bookmark exists");
+            let bookmark = bookmark.untracked();
+            bookmark.visits = count;
+            bookmark.visited = time;
+        }
+    }
+
+    /// Replaces this store's entries with copies of another's, returning the store as it was.
+    fn rebase(&mut self, other: &Store) -> Store {
+        let mut rebased = Store::new();
+        rebased.url_policy = self.url_policy;
+        rebased.normalize_tags = self.normalize_tags;
+        for entry in other.entries.iter().flatten() {
+            rebased.add_entry(entry.clone());
+        }
+
+        let mut old = std::mem::replace(self, rebased);
+        self.path = old.path.take();
+        self.disk = old.disk.take();
+        old
+    }
+
+    /// Takes the bookmark changes made in `other` since `base`, which must not conflict with
+    /// changes made here.
+    fn take_changes(&mut self, base: &Store, other: &Store) {
+        let (base, other, ours) = (keyed(base), keyed(other), keyed(self));
+        let mut replaced = Vec::new();
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+
+        for key in keys([&base, &other, &ours]) {
+            let (b, t, o) = (line(&base, key), line(&other, key), line(&ours, key));
+            if t == o || t == b {
+                continue;
+            }
+            match (other.get(key), ours.get(key)) {
+                (Some((_, _, bookmark)), Some((id, _, _))) => {
+                    replaced.push((*id, (*bookmark).clone()))
+                }
+                (Some((id, _, bookmark)), None) => added.push((*id, (*bookmark).clone())),
+                (None, Some((id, _, _))) => removed.push(*id),
+                (None, None) => {}
+            }
+        }
+
+        // Bookmarks from the other side keep the modification times they have there
+        for (id, bookmark) in replaced {
+            self.replace_untracked(id, bookmark);
+        }
+        removed.sort_unstable();
+        for &id in removed.iter().rev() {
+            self.remove(id);
+        }
+        // Added bookmarks keep the order they have on the other side
+        added.sort_by_key(|(id, _)| *id);
+        for (_, bookmark) in added {
+            self.add_entry(Entry::Bookmark(Box::new(bookmark)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(text: &str) -> Store {
+        let mut store = Store::new();
+        store.read(text).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_merge() {
+        let base =
+            store("a: https://a.example [x]\nb: https://b.example []\nc: https://c.example []\n");
+        // Elsewhere, `a` was retagged, `c` removed and `d` added
+        let theirs = store(
+            "a: https://a.example [y] {modified=2024-01-01T00:00:00Z}\nb: https://b.example []\nd: https://d.example []\n",
+        );
+        // Here, `b` was renamed and `e` added
+        let mut ours = store(
+            "a: https://a.example [x]\nB: https://b.example []\nc: https://c.example []\ne: https://e.example []\n",
+        );
+
+        let merged = ours.merge(&base, &theirs).unwrap();
+        assert_eq!(
+            merged,
+            Merged {
+                added: 1,
+                updated: 1,
+                removed: 1,
+                other: 0
+            }
+        );
+        let names: Vec<_> = ours.iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["a", "B", "e", "d"]);
+        assert_eq!(ours.get(0).unwrap().tags, vec!["y"]);
+        assert_eq!(
+            ours.get(0).unwrap().modified.unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+
+        // Changing the same bookmark on both sides is a conflict, and changes nothing
+        let mut ours = store("a: https://a.example [z]\n");
+        let conflict = ours.merge(&base, &theirs).unwrap_err();
+        assert_eq!(conflict.urls, vec!["https://a.example/"]);
+        assert_eq!(ours.get(0).unwrap().tags, vec!["z"]);
+    }
+
+    #[test]
+    fn test_merge_other_entries() {
+        let base = store("# Links\na: https://a.example []\n");
+        // Elsewhere, a comment and an empty collection were added
+        let theirs = store("# Links\n# More links\na: https://a.example []\n[Music]\n");
+        // Here, a bookmark was added
+        let mut ours = store("# Links\na: https://a.example []\nb: https://b.example []\n");
+
+        let merged = ours.merge(&base, &theirs).unwrap();
+        assert_eq!(
+            merged,
+            Merged {
+                other: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ours.render(),
+            "# Links\n# More links\na: https://a.example/ []\n[Music]\n[/]\nb: https://b.example/ []\n"
+        );
+
+        // Changing comments on both sides is a conflict
+        let mut ours = store("# Mine\na: https://a.example []\n");
+        let conflict = ours.merge(&base, &theirs).unwrap_err();
+        assert!(conflict.prose && conflict.urls.is_empty());
+    }
+
+    #[test]
+    fn test_merge_visits() {
+        let base = store("a: https://a.example [] {visits=1}\n");
+        // The bookmark was opened once elsewhere, and twice here
+        let theirs = store("a: https://a.example [] {visits=2; visited=2024-01-02T00:00:00Z}\n");
+        let mut ours = store("a: https://a.example [] {visits=3; visited=2024-01-01T00:00:00Z}\n");
+
+        let merged = ours.merge(&base, &theirs).unwrap();
+        assert_eq!(merged, Merged::default());
+        let a = ours.get(0).unwrap();
+        assert_eq!(a.visits, 4);
+        assert_eq!(a.visited.unwrap().to_rfc3339(), "2024-01-02T00:00:00+00:00");
+        assert_eq!(a.modified, None);
+    }
+
+    #[test]
+    fn test_save_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.inno");
+        fs::write(&path, "a: https://a.example []\n").unwrap();
+
+        let mut here = Store::new();
+        here.load(&path).unwrap();
+        let mut there = Store::new();
+        there.load(&path).unwrap();
+
+        there.create("b".to_string(), "https://b.example".into(), vec![]);
+        assert_eq!(there.save(&path).unwrap(), None);
+
+        // Saving here keeps what was added there, rather than writing over it, however the
+        // path is spelled
+        here.create("c".to_string(), "https://c.example".into(), vec![]);
+        let merged = here
+            .save(dir.path().join(".").join("store.inno"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged.added, 1);
+        let mut saved = Store::new();
+        saved.load(&path).unwrap();
+        let names: Vec<_> = saved.iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+
+        // A conflicting change is refused unless forced
+        there.set_tags(0, vec!["there".to_string()]);
+        here.set_tags(0, vec!["here".to_string()]);
+        here.save(&path).unwrap();
+        let error = there.save(&path).unwrap_err();
+        assert!(error.downcast_ref::<MergeConflict>().is_some());
+        there.save_force(&path).unwrap();
+        saved = Store::new();
+        saved.load(&path).unwrap();
+        assert_eq!(saved.get(0).unwrap().tags, vec!["there"]);
+    }
+
+    #[test]
+    fn test_load_keeps_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.inno");
+        let other = dir.path().join("other.inno");
+        fs::write(&path, "a: https://a.example []\n").unwrap();
+        fs::write(&other, "b: https://b.example []\n").unwrap();
+
+        // Loading another file adds its bookmarks, but the store still saves to its own file
+        let mut store = Store::new();
+        store.load(&path).unwrap();
+        store.load(&other).unwrap();
+        assert_eq!(store.save_to_path().unwrap(), None);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "a: https://a.example/ []\nb: https://b.example/ []\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&other).unwrap(),
+            "b: https://b.example []\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_through_symlink() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("store.inno");
+        let link = dir.path().join("link.inno");
+        fs::write(&target, "a: https://a.example []\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        symlink(&target, &link).unwrap();
+        assert!(same_file(&link, &target));
+
+        write(&link, "b: https://b.example []\n").unwrap();
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "b: https://b.example []\n"
+        );
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,244 @@
+//! Validated bookmark URLs.
+
+use std::{fmt::Display, str::FromStr};
+
+use super::entry::EntryParseError;
+
+/// Schemes accepted even when they are not followed by `//` (e.g. `mailto:someone@example.com`).
+const KNOWN_SCHEMES: &[&str] = &[
+    "http", "https", "ftp", "ftps", "sftp", "file", "mailto", "ssh", "git", "tel", "sms", "news",
+    "nntp", "irc", "ircs", "xmpp", "magnet", "data", "gopher", "gemini", "urn",
+];
+
+/// The scheme added to URLs that have none under [`UrlPolicy::Prefix`].
+const DEFAULT_SCHEME: &str = "https://";
+
+/// What to do with a URL that does not parse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UrlPolicy {
+    /// Refuse the URL.
+    Reject,
+    /// Keep the URL verbatim; callers should warn about it.
+    Warn,
+    /// Add `https://` to URLs without a scheme, and refuse URLs that still do not parse.
+    #[default]
+    Prefix,
+}
+
+impl FromStr for UrlPolicy {
+    type Err = EntryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(UrlPolicy::Reject),
+            "warn" => Ok(UrlPolicy::Warn),
+            "prefix" => Ok(UrlPolicy::Prefix),
+            _ => Err(EntryParseError::UnknownUrlPolicy(s.to_string())),
+        }
+    }
+}
+
+impl Display for UrlPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlPolicy::Reject => write!(f, "reject"),
+            UrlPolicy::Warn => write!(f, "warn"),
+            UrlPolicy::Prefix => write!(f, "prefix"),
+        }
+    }
+}
+
+/// A bookmark's URL.
+///
+/// Valid URLs are kept parsed, with internationalized host names in their ASCII (punycode) form.
+/// URLs accepted under [`UrlPolicy::Warn`] are kept verbatim.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Url {
+    text: String,
+    parsed: Option<url::Url>,
+}
+
+impl Url {
+    /// Parses a URL, failing if it has no recognizable scheme or is otherwise invalid.
+    pub fn parse(text: &str) -> Result<Self, EntryParseError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(EntryParseError::EmptyUrl);
+        }
+        if !has_scheme(text) {
+            return Err(EntryParseError::MissingScheme(text.to_string()));
+        }
+
+        let parsed =
+            url::Url::parse(text).map_err(|e| EntryParseError::InvalidUrl(text.to_string(), e))?;
+
+        Ok(Self {
+            text: parsed.to_string(),
+            parsed: Some(parsed),
+        })
+    }
+
+    /// Parses a URL according to a policy.
+    pub fn with_policy(text: &str, policy: UrlPolicy) -> Result<Self, EntryParseError> {
+        let text = text.trim();
+        match (Self::parse(text), policy) {
+            (Ok(url), _) => Ok(url),
+            (Err(EntryParseError::EmptyUrl), _) => Err(EntryParseError::EmptyUrl),
+            (Err(_), UrlPolicy::Warn) => Ok(Self::verbatim(text)),
+            (Err(EntryParseError::MissingScheme(_)), UrlPolicy::Prefix) => {
+                let url = Self::parse(&format!("{}{}", DEFAULT_SCHEME, text))?;
+                // Without a scheme, "foo" would otherwise become the host of https://foo/
+                let plausible = match url.parsed.as_ref().and_then(url::Url::host) {
+                    Some(url::Host::Domain(domain)) => {
+                        domain.contains('.') || domain == "localhost"
+                    }
+                    Some(_) => true,
+                    None => false,
+                };
+                if !plausible {
+                    return Err(EntryParseError::MissingScheme(text.to_string()));
+                }
+                Ok(url)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Keeps text as a URL without validating it.
+    pub fn verbatim(text: &str) -> Self {
+        Self {
+            text: text.trim().to_string(),
+            parsed: None,
+        }
+    }
+
+    /// Returns the parsed URL, if it is valid.
+    pub fn parsed(&self) -> Option<&url::Url> {
+        self.parsed.as_ref()
+    }
+
+    /// Returns `true` if the URL is valid.
+    pub fn is_valid(&self) -> bool {
+        self.parsed.is_some()
+    }
+
+    /// Returns the URL as text, with host names in their ASCII form.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the URL's host, decoded from punycode for display.
+    pub fn host(&self) -> Option<String> {
+        let host = self.parsed.as_ref()?.host_str()?;
+        Some(idna::domain_to_unicode(host).0)
+    }
+}
+
+/// Returns `true` if text starts with a scheme that we accept.
+fn has_scheme(text: &str) -> bool {
+    let Some((scheme, rest)) = text.split_once(':') else {
+        return false;
+    };
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    // "localhost:8080" or "example.com:80/path" are hosts with ports, not schemes
+    valid
+        && (rest.starts_with("//")
+            || KNOWN_SCHEMES
+                .iter()
+                .any(|known| scheme.eq_ignore_ascii_case(known)))
+}
+
+impl Display for Url {
+    /// Writes the URL with internationalized host names in their Unicode form.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(host) = self
+            .parsed
+            .as_ref()
+            .and_then(url::Url::host_str)
+            .filter(|host| host.contains("xn--"))
+        else {
+            return write!(f, "{}", self.text);
+        };
+
+        let unicode = idna::domain_to_unicode(host).0;
+        write!(f, "{}", self.text.replacen(host, &unicode, 1))
+    }
+}
+
+impl FromStr for Url {
+    type Err = EntryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<&str> for Url {
+    /// Parses a URL if possible, otherwise keeps it verbatim.
+    fn from(text: &str) -> Self {
+        Self::parse(text).unwrap_or_else(|_| Self::verbatim(text))
+    }
+}
+
+impl From<String> for Url {
+    /// Parses a URL if possible, otherwise keeps it verbatim.
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let url = Url::parse("HTTPS://Example.com").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/");
+
+        assert!(Url::parse("mailto:someone@example.com").is_ok());
+        assert!(Url::parse("ssh://git@example.com/repo.git").is_ok());
+        assert!(Url::parse("file:///home/user/notes.txt").is_ok());
+
+        assert!(matches!(
+            Url::parse("bar"),
+            Err(EntryParseError::MissingScheme(_))
+        ));
+        assert!(matches!(
+            Url::parse("localhost:8080"),
+            Err(EntryParseError::MissingScheme(_))
+        ));
+        assert!(matches!(
+            Url::parse("https://exa mple.com"),
+            Err(EntryParseError::InvalidUrl(..))
+        ));
+    }
+
+    #[test]
+    fn test_policies() {
+        assert!(Url::with_policy("example.com", UrlPolicy::Reject).is_err());
+        assert_eq!(
+            Url::with_policy("example.com/a", UrlPolicy::Prefix)
+                .unwrap()
+                .as_str(),
+            "https://example.com/a"
+        );
+        assert!(Url::with_policy("bar", UrlPolicy::Prefix).is_err());
+
+        let url = Url::with_policy("bar", UrlPolicy::Warn).unwrap();
+        assert!(!url.is_valid());
+        assert_eq!(url.as_str(), "bar");
+    }
+
+    #[test]
+    fn test_idn() {
+        let url = Url::parse("https://bücher.example/katalog").unwrap();
+        assert_eq!(url.as_str(), "https://xn--bcher-kva.example/katalog");
+        assert_eq!(url.to_string(), "https://bücher.example/katalog");
+        assert_eq!(url.host().unwrap(), "bücher.example");
+    }
+}
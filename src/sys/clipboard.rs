@@ -0,0 +1,138 @@
+//! Copying text to the system clipboard.
+
+use std::{
+    env,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+
+/// The environment variable that overrides clipboard detection.
+///
+/// It is either `osc52` or a command that reads the text to copy from standard input.
+pub const CLIPBOARD_VAR: &str = "INNO_CLIPBOARD";
+
+/// Somewhere text can be copied to.
+pub trait Clipboard {
+    /// Copies text to the clipboard.
+    fn copy(&mut self, text: &str) -> Result<()>;
+}
+
+/// Copies text by writing an OSC 52 escape sequence, which the terminal turns into a copy.
+///
+/// This works over SSH, as long as the terminal supports it.
+pub struct Osc52<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Osc52<W> {
+    /// Creates a clipboard that writes escape sequences to `out`.
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> Clipboard for Osc52<W> {
+    fn copy(&mut self, text: &str) -> Result<()> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+        write!(self.out, "\x1b]52;c;{}\x07", encoded)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Copies text by piping it to a command such as `wl-copy` or `xclip`.
+pub struct CommandClipboard {
+    words: Vec<String>,
+}
+
+impl CommandClipboard {
+    /// Creates a clipboard from a command line; fails if it is empty or cannot be split.
+    pub fn new(command: &str) -> Result<Self> {
+        let words = shlex::split(command)
+            .filter(|words| !words.is_empty())
+            .with_context(|| format!("Invalid clipboard command: '{}'", command))?;
+        Ok(Self { words })
+    }
+}
+
+impl Clipboard for CommandClipboard {
+    fn copy(&mut self, text: &str) -> Result<()> {
+        let program = &self.words[0];
+        let mut child = Command::new(program)
+            .args(&self.words[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", program))?;
+
+        child
+            .stdin
+            .take()
+            .context("Clipboard command has no standard input")?
+            .write_all(text.as_bytes())?;
+
+        let status = child.wait()?;
+        if !status.success() {
+            anyhow::bail!("'{}' exited with {}", program, status);
+        }
+        Ok(())
+    }
+}
+
+/// Picks a clipboard for the current environment.
+///
+/// `$INNO_CLIPBOARD` wins; otherwise a clipboard command for the running display server is used
+/// if one is installed, falling back to OSC 52.
+pub fn detect() -> Result<Box<dyn Clipboard>> {
+    match env::var(CLIPBOARD_VAR) {
+        Ok(choice) if choice == "osc52" => return Ok(Box::new(Osc52::new(std::io::stdout()))),
+        Ok(command) => return Ok(Box::new(CommandClipboard::new(&command)?)),
+        Err(_) => {}
+    }
+
+    let candidates = [
+        (cfg!(target_os = "macos"), "pbcopy"),
+        (env::var_os("WAYLAND_DISPLAY").is_some(), "wl-copy"),
+        (
+            env::var_os("DISPLAY").is_some(),
+            "xclip -selection clipboard",
+        ),
+    ];
+    for (applies, command) in candidates {
+        let program = command.split(' ').next().unwrap_or(command);
+        if applies && on_path(program) {
+            return Ok(Box::new(CommandClipboard::new(command)?));
+        }
+    }
+
+    Ok(Box::new(Osc52::new(std::io::stdout())))
+}
+
+/// Returns `true` if a program is found in one of the directories in `$PATH`.
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| is_file(&dir.join(program))))
+}
+
+fn is_file(path: &Path) -> bool {
+    path.metadata().is_ok_and(|metadata| metadata.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        let mut out = Vec::new();
+        Osc52::new(&mut out).copy("https://example.com/").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b]52;c;aHR0cHM6Ly9leGFtcGxlLmNvbS8=\x07"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Editing text in the user's editor.
+
+use std::{fs, io::Write, process::Command};
+
+use anyhow::{Context, Result};
+
+/// The editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// An editor command, such as `vim` or `code --wait`.
+pub struct Editor {
+    command: String,
+}
+
+impl Editor {
+    /// Creates an editor from a command line; the file to edit is passed as the last argument.
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+
+    /// Creates an editor from `$VISUAL` or `$EDITOR`, falling back to `vi`.
+    pub fn from_env() -> Self {
+        let command = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|command| !command.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+        Self::new(command)
+    }
+
+    /// Opens text in the editor and returns it once the editor exits.
+    ///
+    /// The text is written to a temporary file with the given extension, so that editors can
+    /// pick a syntax.
+    pub fn edit(&self, text: &str, extension: &str) -> Result<String> {
+        let words = shlex::split(&self.command)
+            .filter(|words| !words.is_empty())
+            .with_context(|| format!("Invalid editor command: '{}'", self.command))?;
+
+        let mut file = tempfile::Builder::new()
+            .prefix("inno-")
+            .suffix(&format!(".{}", extension))
+            .tempfile()
+            .context("Failed to create a temporary file")?;
+        file.write_all(text.as_bytes())?;
+        file.flush()?;
+
+        let status = Command::new(&words[0])
+            .args(&words[1..])
+            .arg(file.path())
+            .status()
+            .with_context(|| format!("Failed to run '{}'", words[0]))?;
+        if !status.success() {
+            anyhow::bail!("Editor exited with {}; changes discarded", status);
+        }
+
+        // Editors often replace the file rather than writing to it, so read it by path
+        fs::read_to_string(file.path()).context("Failed to read the edited file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let editor = Editor::new(r#"sh -c 'printf "%s, edited" "$(cat "$1")" > "$1"' sh"#);
+        assert_eq!(editor.edit("text", "txt").unwrap(), "text, edited");
+
+        assert!(Editor::new("false").edit("text", "txt").is_err());
+    }
+}
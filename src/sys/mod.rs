@@ -0,0 +1,6 @@
+//! Integration with programs outside inno: browsers, the clipboard, editors and pagers.
+
+pub mod clipboard;
+pub mod editor;
+pub mod opener;
+pub mod pager;
@@ -0,0 +1,103 @@
+//! Launching URLs in the system browser or a user-chosen command.
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::{Context, Result};
+
+/// The environment variable that overrides the opener command.
+pub const OPENER_VAR: &str = "INNO_OPENER";
+
+/// The placeholder replaced by the URL in an opener command template.
+const PLACEHOLDER: &str = "{url}";
+
+/// Returns the platform's default command for opening URLs.
+pub fn default_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "cmd /c start \"\""
+    } else {
+        "xdg-open"
+    }
+}
+
+/// A command template for opening URLs, such as `firefox --new-tab {url}`.
+pub struct Opener {
+    template: String,
+}
+
+impl Opener {
+    /// Creates an opener from a command template.
+    ///
+    /// Every `{url}` in the template is replaced by the URL; if there is none, the URL is
+    /// passed as the last argument.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Creates an opener from `$INNO_OPENER`, falling back to the platform default.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(OPENER_VAR).unwrap_or_else(|_| default_command().to_string()))
+    }
+
+    /// Returns the program and arguments that would open a URL.
+    pub fn command_line(&self, url: &str) -> Result<Vec<String>> {
+        let mut words = shlex::split(&self.template)
+            .filter(|words| !words.is_empty())
+            .with_context(|| format!("Invalid opener command: '{}'", self.template))?;
+
+        if words.iter().any(|word| word.contains(PLACEHOLDER)) {
+            for word in &mut words {
+                *word = word.replace(PLACEHOLDER, url);
+            }
+        } else {
+            words.push(url.to_string());
+        }
+
+        Ok(words)
+    }
+
+    /// Opens a URL without waiting for the opener to exit.
+    ///
+    /// The opener is waited for on a thread of its own, so that it does not linger as a zombie
+    /// process once it exits.
+    pub fn open(&self, url: &str) -> Result<()> {
+        let words = self.command_line(url)?;
+        let mut child = Command::new(&words[0])
+            .args(&words[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", words[0]))?;
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        let url = "https://example.com/?a=1&b=2";
+
+        assert_eq!(
+            Opener::new("xdg-open").command_line(url).unwrap(),
+            vec!["xdg-open", url]
+        );
+        assert_eq!(
+            Opener::new("firefox --new-tab '{url}' --foreground")
+                .command_line(url)
+                .unwrap(),
+            vec!["firefox", "--new-tab", url, "--foreground"]
+        );
+        assert!(Opener::new("").command_line(url).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Paging long output.
+
+use std::{
+    io::{IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result};
+
+/// The pager used when `$PAGER` is not set.
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Prints text, piping it through `$PAGER` if it is too tall for the terminal.
+///
+/// Output that is not going to a terminal, or with `$PAGER` set to an empty string, is printed
+/// directly.
+pub fn page(text: &str) -> Result<()> {
+    let command = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let fits = terminal_size::terminal_size()
+        .is_none_or(|(_, height)| text.lines().count() < usize::from(height.0));
+
+    if fits || command.trim().is_empty() || !std::io::stdout().is_terminal() {
+        print!("{}", text);
+        return Ok(());
+    }
+
+    let words = shlex::split(&command)
+        .filter(|words| !words.is_empty())
+        .with_context(|| format!("Invalid pager command: '{}'", command))?;
+    let Ok(mut child) = Command::new(&words[0])
+        .args(&words[1..])
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        // Without a working pager, printing everything is better than nothing
+        print!("{}", text);
+        return Ok(());
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when the user quits early
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+
+    Ok(())
+}